@@ -1,15 +1,27 @@
-use std::{collections::HashMap, fmt::Display, path::Path, str::FromStr};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
+use std::time::Duration;
 
 use figment::{
     providers::{Env, Format, Toml},
     Figment,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_inline_default::serde_inline_default;
 
-use crate::{DeserializedRegex, OptionalJson};
+use crate::{
+    responders::{RegisteredResponder, Responder},
+    DeserializedRegex, OptionalJson,
+};
 
-fn generate_hash() -> String {
+pub(crate) fn generate_hash() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let mut hash = String::new();
@@ -30,6 +42,55 @@ pub struct AppConfig {
     /// A list of mock resources to be used by the server. For more information see [MockResource].
     #[serde(default)]
     pub mock_resources: Vec<MockResource>,
+    /// A list of functions that are backed by an in-memory table instead of static data. For more information see [StatefulFunction].
+    #[serde(default)]
+    pub stateful_functions: Vec<StatefulFunction>,
+    /// The latency to add to every response. For more information see [LatencyConfig].
+    ///
+    /// Mock resources can override this with their own [latency][MockResource::latency].
+    #[serde(default)]
+    pub latency: Option<LatencyConfig>,
+    /// The faults to inject into responses. For more information see [FaultConfig].
+    ///
+    /// Mock resources can override this with their own [faults][MockResource::faults].
+    #[serde(default)]
+    pub faults: Option<FaultConfig>,
+    /// The seed for the random number generator used for fault injection.
+    ///
+    /// If not provided, a random seed is used. The seed is logged on startup, so failing test runs can be reproduced.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Whether to enable the admin API under `/__admin`, which allows adding, replacing and removing
+    /// mock resources at runtime and resetting the server state.
+    #[serde(default)]
+    pub admin_api: bool,
+    /// The number of received requests to keep in the journal. Once full, the oldest requests are discarded.
+    ///
+    /// If not provided, the last 1000 requests are kept. Set to `0` to disable the journal.
+    #[serde(default)]
+    pub journal_capacity: Option<usize>,
+    /// Whether to explain unmatched EXECJSON requests in the response, in a non-standard `WWSVC_MOCK_DIAGNOSTICS` field.
+    ///
+    /// Unmatched requests are always logged and recorded in the journal along with the closest mock resources.
+    #[serde(default)]
+    pub diagnostics: bool,
+    /// Forwards requests that no mock resource answers to a real WEBWARE and optionally records them.
+    /// For more information see [ProxyConfig].
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+    /// A cassette of recorded interactions to replay in order. For more information see [Cassette].
+    #[serde(default)]
+    pub cassette: Option<Cassette>,
+    /// The contracts of the functions, which requests and the configured responses are validated against. For more
+    /// information see [FunctionSchema].
+    #[serde(default)]
+    pub schemas: Vec<FunctionSchema>,
+    /// TOML or JSON files with a `schemas` list to load additional [schemas][AppConfig::schemas] from.
+    #[serde(default)]
+    pub schema_files: Vec<String>,
+    /// Responders computing responses in Rust code. They can only be added with [AppConfig::with_responder].
+    #[serde(skip)]
+    pub responders: Vec<RegisteredResponder>,
     /// Whether to enable the debug middleware for logging requests and responses.
     #[serde(default)]
     pub debug: bool,
@@ -40,6 +101,7 @@ impl AppConfig {
     /// 
     /// The environment variables are prefixed with `APP__` and split by `__`. For example, the
     /// `server.bind_address` field can be set by the `APP__SERVER__BIND_ADDRESS` environment.
+    #[allow(clippy::result_large_err)]
     pub fn new() -> Result<Self, figment::Error> {
         Figment::new()
             .merge(Toml::file("config.toml"))
@@ -51,6 +113,7 @@ impl AppConfig {
     ///
     /// The environment variables are prefixed with `APP__` and split by `__`. For example, the
     /// `server.bind_address` field can be set by the `APP__SERVER__BIND_ADDRESS` environment.
+    #[allow(clippy::result_large_err)]
     pub fn from_file(file: &Path) -> Result<Self, figment::Error> {
         Figment::new()
             .merge(Toml::file(file))
@@ -63,6 +126,50 @@ impl AppConfig {
         self.mock_resources.push(resource);
         self
     }
+
+    /// Adds a [Responder] that answers all calls to the given function and method, regardless of the revision and
+    /// parameters. Responders take precedence over mock resources.
+    ///
+    /// ```rust,ignore
+    /// let config = AppConfig::default().with_responder("ARTIKEL", MockResourceMethod::Get, |request: &WebserviceRequest| {
+    ///     ServiceResponse::ok(json!({ "ARTIKEL": { "ARTNR": request.parameter("ARTNR") } }))
+    /// });
+    /// ```
+    pub fn with_responder(
+        mut self,
+        function: &str,
+        method: MockResourceMethod,
+        responder: impl Responder + 'static,
+    ) -> Self {
+        self.responders.push(RegisteredResponder {
+            function: function.to_string(),
+            method,
+            responder: Arc::new(responder),
+        });
+        self
+    }
+
+    /// Adds a [stateful function][StatefulFunction] to the configuration.
+    pub fn with_stateful_function(mut self, function: StatefulFunction) -> Self {
+        self.stateful_functions.push(function);
+        self
+    }
+
+    /// Returns the [schemas][AppConfig::schemas], along with the ones from the [schema files][AppConfig::schema_files].
+    pub fn load_schemas(&self) -> anyhow::Result<Vec<FunctionSchema>> {
+        let mut schemas = self.schemas.clone();
+        for file in &self.schema_files {
+            let schema_file: SchemaFile = if file.ends_with(".json") {
+                let contents = std::fs::read_to_string(file)
+                    .map_err(|err| anyhow::anyhow!("Failed to read {}: {}", file, err))?;
+                serde_json::from_str(&contents)?
+            } else {
+                Figment::from(Toml::file_exact(file)).extract()?
+            };
+            schemas.extend(schema_file.schemas);
+        }
+        Ok(schemas)
+    }
 }
 
 /// The server configuration. This config only applies for the binary, not the library.
@@ -81,6 +188,101 @@ pub struct WebwareConfig {
     /// The credentials that the webservices will accept. See [CredentialsConfig] for more information.
     #[serde(default)]
     pub credentials: CredentialsConfig,
+    /// Whether EXECJSON calls have to be authenticated like DEREGISTER calls.
+    ///
+    /// If enabled, the service pass and application hash in the `WWSVC_PASSINFO` block as well as the
+    /// `WWSVC-*` headers (including the `WWSVC-HASH`) are checked on every call. Defaults to `false`.
+    #[serde(default)]
+    pub authenticate_requests: bool,
+    /// The configuration of the service pass registry. See [SessionsConfig] for more information.
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+    /// Additional applications that can register with the webservices, each with their own credentials.
+    ///
+    /// The application configured by [WebwareConfig::webservices] and [WebwareConfig::credentials] is always
+    /// registered as well. See [ApplicationConfig] for more information.
+    #[serde(default)]
+    pub applications: Vec<ApplicationConfig>,
+}
+
+impl WebwareConfig {
+    /// Returns all applications that can register with the webservices, starting with the default application.
+    pub fn all_applications(&self) -> Vec<ApplicationConfig> {
+        let default_application = ApplicationConfig {
+            name: None,
+            webservices: self.webservices.clone(),
+            credentials: self.credentials.clone(),
+            mock_resources: None,
+        };
+
+        std::iter::once(default_application)
+            .chain(self.applications.iter().cloned())
+            .collect()
+    }
+}
+
+/// The configuration of the proxy mode, in which EXECJSON requests that no mock resource or stateful function answers
+/// are forwarded to a real WEBWARE.
+///
+/// The server registers with the upstream WEBWARE using its own [webservices][ProxyConfig::webservices] configuration and
+/// re-signs every forwarded request, so clients keep using the credentials of the mock server.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProxyConfig {
+    /// The URL of the upstream WEBWARE, e.g. `https://erp.example.com/`.
+    pub url: String,
+    /// The webservices configuration used to register with the upstream WEBWARE.
+    pub webservices: WebservicesConfig,
+    /// The directory to record forwarded requests into.
+    ///
+    /// Each exchange is appended to `mock_resources.toml` in this directory as a [MockResource], with its data written to
+    /// the `data` subdirectory. Recorded resources are also added to the running server, so repeated requests aren't
+    /// forwarded again. If not provided, requests are only forwarded.
+    #[serde(default)]
+    pub record_directory: Option<String>,
+}
+
+/// An application that can register with the webservices.
+///
+/// This allows a single server to stand in for a WEBWARE that is shared by several integrations.
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct ApplicationConfig {
+    /// An optional name for the application, only used for logging.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The webservices configuration of the application, see [WebservicesConfig] for more information.
+    #[serde(default)]
+    pub webservices: WebservicesConfig,
+    /// The credentials of the application, see [CredentialsConfig] for more information.
+    #[serde(default)]
+    pub credentials: CredentialsConfig,
+    /// The mock resources this application is allowed to call.
+    ///
+    /// If not provided, the application can call all resources in [AppConfig::mock_resources].
+    #[serde(default)]
+    pub mock_resources: Option<Vec<MockResource>>,
+}
+
+/// The configuration of the service pass registry.
+///
+/// If enabled, every REGISTER call issues a fresh service pass and application ID instead of the
+/// ones in [CredentialsConfig]. Service passes that were never issued, were deregistered or have
+/// expired are rejected by DEREGISTER and, if [WebwareConfig::authenticate_requests] is enabled,
+/// by EXECJSON.
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct SessionsConfig {
+    /// Whether to issue a fresh service pass per REGISTER call. Defaults to `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// The maximum number of service passes that can be active at the same time.
+    ///
+    /// If not provided, there is no limit.
+    #[serde(default)]
+    pub max_service_passes: Option<usize>,
+    /// The time in milliseconds after which an unused service pass expires.
+    ///
+    /// If not provided, service passes never expire.
+    #[serde(default)]
+    pub idle_timeout_ms: Option<u64>,
 }
 
 /// The credentials configuration for the webservices.
@@ -144,8 +346,8 @@ impl Default for WebservicesConfig {
     }
 }
 
-/// A data source that can be either a file path, a string or empty.
-#[derive(Deserialize, Debug, Clone)]
+/// A data source that can be either a file path, a string, a script, generated records or empty.
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum FileOrString {
     /// A file path to read the data from.
@@ -158,42 +360,207 @@ pub enum FileOrString {
         /// The string value.
         value: String
     },
+    /// A [Rhai](https://rhai.rs) script that computes the response.
+    ///
+    /// The script gets the request in the `request` variable, a map of `function`, `revision`, `parameters`,
+    /// `service_pass`, `app_hash`, `request_id` and `previous_calls`, the number of previous calls to the resource.
+    /// Its return value is used as the response body. To respond with a custom COMRESULT, the script can assign a
+    /// map like `#{ status: 409, info: "Artikel existiert bereits" }` (see [ComResultConfig]) to `comresult`.
+    ///
+    /// The `state` variable is a map that is shared between all scripts and kept between calls.
+    Script {
+        /// A file containing the script.
+        #[serde(default)]
+        file: Option<String>,
+        /// The script itself, if no file is given.
+        #[serde(default)]
+        source: Option<String>,
+    },
+    /// A list response with `count` generated records of an entity, see [GeneratedEntity].
+    ///
+    /// The records look like real ERP data and are the same for the same `seed`. Unless the resource has a
+    /// [dataset][MockResource::dataset], GET requests are handled with the default [DatasetConfig], e.g. for `FELDER`.
+    Generated {
+        /// The entity to generate records of.
+        entity: GeneratedEntity,
+        /// The number of records.
+        count: usize,
+        /// The seed of the random number generator. Defaults to `0`.
+        #[serde(default)]
+        seed: u64,
+    },
     /// An empty data source.
     Empty,
 }
 
+/// An entity that records can be [generated][FileOrString::Generated] for.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum GeneratedEntity {
+    /// Articles in an `ARTIKELLISTE`, with `ART_1_25`, `ART_51_60`, `ART_BEZEICHNUNG`, `ART_WARENGR`, `ART_EINHEIT`,
+    /// `ART_PREIS` and `ART_MWST`.
+    ///
+    /// Serializes and deserializes to and from `ARTIKEL`.
+    #[serde(rename = "ARTIKEL")]
+    Artikel,
+    /// Customers in a `KUNDENLISTE`, with `KUN_1_10`, `KUN_11_40`, the address, `KUN_UST_ID` and `KUN_MWST`.
+    ///
+    /// Serializes and deserializes to and from `KUNDE`.
+    #[serde(rename = "KUNDE")]
+    Kunde,
+    /// Suppliers in a `LIEFERANTENLISTE`, with `LIF_1_10`, `LIF_11_40`, the address and `LIF_UST_ID`.
+    ///
+    /// Serializes and deserializes to and from `LIEFERANT`.
+    #[serde(rename = "LIEFERANT")]
+    Lieferant,
+    /// Orders in an `AUFTRAGSLISTE`, with `AUF_1_10`, `AUF_KUNDE`, `AUF_DATUM`, `AUF_NETTO`, `AUF_MWST`, `AUF_BRUTTO`
+    /// and `AUF_STATUS`.
+    ///
+    /// Serializes and deserializes to and from `AUFTRAG`.
+    #[serde(rename = "AUFTRAG")]
+    Auftrag,
+}
+
+impl GeneratedEntity {
+    /// The name of the list in responses, e.g. `ARTIKELLISTE`.
+    pub fn list_name(&self) -> &'static str {
+        match self {
+            GeneratedEntity::Artikel => "ARTIKELLISTE",
+            GeneratedEntity::Kunde => "KUNDENLISTE",
+            GeneratedEntity::Lieferant => "LIEFERANTENLISTE",
+            GeneratedEntity::Auftrag => "AUFTRAGSLISTE",
+        }
+    }
+
+    /// The name of a single record in responses, e.g. `ARTIKEL`.
+    pub fn item_name(&self) -> &'static str {
+        match self {
+            GeneratedEntity::Artikel => "ARTIKEL",
+            GeneratedEntity::Kunde => "KUNDE",
+            GeneratedEntity::Lieferant => "LIEFERANT",
+            GeneratedEntity::Auftrag => "AUFTRAG",
+        }
+    }
+}
+
 impl FileOrString {
     /// Returns the data source as a string.
     /// 
     /// If the data source is a file, it will read the file and return the contents.
     /// If the data source is a string, it will return the string.
+    /// If the data source is a script, it will return the script itself.
+    /// If the data source generates records, it will return them as JSON.
     /// If the data source is empty, it will return an empty string.
     pub fn as_string(&self) -> String {
         match self {
             FileOrString::File { file } => std::fs::read_to_string(file).unwrap(),
             FileOrString::String { value } => value.clone(),
+            FileOrString::Script { file: Some(file), .. } => std::fs::read_to_string(file).unwrap(),
+            FileOrString::Script { file: None, source } => source.clone().unwrap_or_default(),
+            FileOrString::Generated { entity, count, seed } => {
+                crate::generator::generate(*entity, *count, *seed).to_string()
+            }
             FileOrString::Empty => "".to_string(),
         }
     }
 
+    fn empty() -> Self {
+        FileOrString::Empty
+    }
+
+    /// Reads the data source and parses it as JSON.
+    ///
+    /// Unlike [FileOrString::as_json_value], this returns an error if the file cannot be read or the data isn't valid JSON.
+    pub fn read_json(&self) -> anyhow::Result<Option<serde_json::Value>> {
+        match self {
+            FileOrString::File { file } => {
+                let contents = std::fs::read_to_string(file)
+                    .map_err(|err| anyhow::anyhow!("Failed to read {}: {}", file, err))?;
+                Ok(Some(serde_json::from_str(&contents)?))
+            }
+            FileOrString::String { value } => Ok(Some(serde_json::from_str(value)?)),
+            FileOrString::Script { .. } => anyhow::bail!("A script can't be read as JSON"),
+            FileOrString::Generated { entity, count, seed } => {
+                Ok(Some(crate::generator::generate(*entity, *count, *seed)))
+            }
+            FileOrString::Empty => Ok(None),
+        }
+    }
+
     /// Returns the data source as an [OptionalJson] value.
     /// 
     /// If the data source is a file, it will read the file and parse it as JSON.
     /// If the data source is a string, it will parse the string as JSON.
-    /// If the data source is empty, it will return `None`.
+    /// If the data source generates records, it will return them.
+    /// If the data source is a script or empty, it will return `None`, as scripts need to be run by the server.
     pub fn as_json_value(&self) -> OptionalJson {
         match self {
             FileOrString::File { file: _ } => OptionalJson(Some(serde_json::from_str(&self.as_string()).unwrap())),
             FileOrString::String { value: _ } => OptionalJson(Some(serde_json::from_str(&self.as_string()).unwrap())),
-            FileOrString::Empty => OptionalJson(None),
+            FileOrString::Generated { entity, count, seed } => {
+                OptionalJson(Some(crate::generator::generate(*entity, *count, *seed)))
+            }
+            FileOrString::Script { .. } | FileOrString::Empty => OptionalJson(None),
         }
     }
 }
 
+/// A function that is backed by an in-memory table.
+///
+/// Unlike a [MockResource], a stateful function remembers previous calls: `INSERT` adds a record,
+/// `PUT` updates the record with the given key, `DELETE` removes it and `GET` returns the current
+/// state of the table in the usual list envelope, e.g. `ARTIKELLISTE`/`ANZAHL`/`ARTIKEL`.
+///
+/// Stateful functions answer to every revision. Mock resources take precedence, so you can still
+/// mock specific calls (e.g. error responses) for a stateful function.
+#[derive(Deserialize, Debug, Clone)]
+pub struct StatefulFunction {
+    /// The function name, without the method. For example, `ARTIKEL`.
+    pub function: String,
+    /// The field that uniquely identifies a record. For example, `ART_1_25`.
+    pub key_field: String,
+    /// The request parameter that contains the key of a record. For example, `ARTNR`.
+    ///
+    /// If not provided, the [key field][StatefulFunction::key_field] is used as the parameter name.
+    #[serde(default)]
+    pub key_parameter: Option<String>,
+    /// The name of the list in `GET` responses.
+    ///
+    /// If not provided, the function name followed by `LISTE` is used, e.g. `ARTIKELLISTE`.
+    #[serde(default)]
+    pub list_name: Option<String>,
+    /// The name of a single record in responses.
+    ///
+    /// If not provided, the function name is used, e.g. `ARTIKEL`.
+    #[serde(default)]
+    pub item_name: Option<String>,
+    /// The data the table is seeded with. This can either be a list response, as returned by `GET`, or a plain array of records.
+    #[serde(default = "FileOrString::empty")]
+    pub seed: FileOrString,
+}
+
+impl StatefulFunction {
+    /// Returns the name of the request parameter that contains the key of a record.
+    pub fn key_parameter(&self) -> &str {
+        self.key_parameter.as_deref().unwrap_or(&self.key_field)
+    }
+
+    /// Returns the name of the list in `GET` responses.
+    pub fn list_name(&self) -> String {
+        self.list_name
+            .clone()
+            .unwrap_or_else(|| format!("{}LISTE", self.function))
+    }
+
+    /// Returns the name of a single record in responses.
+    pub fn item_name(&self) -> &str {
+        self.item_name.as_deref().unwrap_or(&self.function)
+    }
+}
+
 /// The method of the mock resource.
 /// 
 /// These are the methods that the WEBSERVICES accept for functions.
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum MockResourceMethod {
     /// The GET method, used for reading data.
     /// 
@@ -249,119 +616,1455 @@ impl Display for MockResourceMethod {
     }
 }
 
-/// A mock resource that the server will use to mock the WEBSERVICES.
-/// 
-/// The resource will only return the data from the data source if the function, method, revision and parameters match.
-/// There is currently no way to do wildcard matching.
-#[derive(Deserialize, Debug, Clone)]
-pub struct MockResource {
-    /// The [data source][FileOrString] for the mock resource.
-    pub data_source: FileOrString,
-    /// The function name for the mock resource.
-    /// 
-    /// This is the name of the function but without the method. For example, `ARTIKEL`.
-    pub function: String,
-    /// The method for the mock resource. See [MockResourceMethod] for more information.
-    pub method: MockResourceMethod,
-    /// The revision for the mock resource.
-    pub revision: u32,
+/// The revision(s) of a function that a mock resource answers to.
+///
+/// In the configuration, a revision can be given as a number (`revision = 3`), as a range
+/// string (`revision = "2-3"`), as a table (`revision = { min = 2, max = 3 }`) or as `"any"`
+/// (or `"*"`) if the resource doesn't care about the revision at all.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MockResourceRevision {
+    /// Only the given revision matches.
+    Exact(u32),
+    /// All revisions between `min` and `max` (both inclusive) match.
+    Range {
+        /// The lowest revision that matches.
+        min: u32,
+        /// The highest revision that matches.
+        max: u32,
+    },
+    /// Every revision matches.
+    #[default]
+    Any,
+}
 
-    /// The parameters for the mock resource.
-    pub parameters: Option<HashMap<String, DeserializedRegex>>,
+impl MockResourceRevision {
+    /// Returns whether the given revision is covered by this revision matcher.
+    pub fn matches(&self, revision: u32) -> bool {
+        match self {
+            MockResourceRevision::Exact(exact) => *exact == revision,
+            MockResourceRevision::Range { min, max } => (*min..=*max).contains(&revision),
+            MockResourceRevision::Any => true,
+        }
+    }
+
+    fn bounds(&self) -> (u32, u32) {
+        match self {
+            MockResourceRevision::Exact(revision) => (*revision, *revision),
+            MockResourceRevision::Range { min, max } => (*min, *max),
+            MockResourceRevision::Any => (u32::MIN, u32::MAX),
+        }
+    }
+
+    /// Returns whether any revision is covered by both revision matchers.
+    pub fn overlaps(&self, other: &MockResourceRevision) -> bool {
+        let (min, max) = self.bounds();
+        let (other_min, other_max) = other.bounds();
+        min <= other_max && other_min <= max
+    }
 }
 
-impl Display for MockResource {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "MockResource {{ function: {}, method: {}, revision: {}, parameters: {} }}",
-            self.function, self.method, self.revision, match self.parameters {
-                Some(ref parameters) => serde_json::to_string(parameters).unwrap(),
-                None => "None".to_string(),
+impl From<u32> for MockResourceRevision {
+    fn from(revision: u32) -> Self {
+        MockResourceRevision::Exact(revision)
+    }
+}
+
+impl FromStr for MockResourceRevision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("any") || s == "*" {
+            return Ok(MockResourceRevision::Any);
+        }
+
+        if let Some((min, max)) = s.split_once('-') {
+            let min = min.trim().parse::<u32>().map_err(|_| format!("Invalid revision range: {}", s))?;
+            let max = max.trim().parse::<u32>().map_err(|_| format!("Invalid revision range: {}", s))?;
+            if min > max {
+                return Err(format!("Invalid revision range: {}", s));
             }
-        )
+            return Ok(MockResourceRevision::Range { min, max });
+        }
+
+        s.parse::<u32>()
+            .map(MockResourceRevision::Exact)
+            .map_err(|_| format!("Invalid revision: {}", s))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::str::FromStr;
+impl Display for MockResourceRevision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MockResourceRevision::Exact(revision) => write!(f, "{}", revision),
+            MockResourceRevision::Range { min, max } => write!(f, "{}-{}", min, max),
+            MockResourceRevision::Any => write!(f, "any"),
+        }
+    }
+}
 
-    use pretty_assertions::assert_eq;
+impl<'de> Deserialize<'de> for MockResourceRevision {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawRevision {
+            Number(u32),
+            String(String),
+            Range { min: u32, max: u32 },
+        }
 
-    use crate::DeserializedRegex;
+        match RawRevision::deserialize(deserializer)? {
+            RawRevision::Number(revision) => Ok(MockResourceRevision::Exact(revision)),
+            RawRevision::String(s) => MockResourceRevision::from_str(&s).map_err(serde::de::Error::custom),
+            RawRevision::Range { min, max } if min <= max => Ok(MockResourceRevision::Range { min, max }),
+            RawRevision::Range { min, max } => Err(serde::de::Error::custom(format!("Invalid revision range: {}-{}", min, max))),
+        }
+    }
+}
 
-    macro_rules! one_line_assert_eq {
-        ($fn:ident, $left:expr, $right:expr) => {
-            #[test]
-            fn $fn() {
-                assert_eq!($left, $right);
-            }
+impl Serialize for MockResourceRevision {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            MockResourceRevision::Exact(revision) => serializer.serialize_u32(*revision),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+/// How a mock resource matches a request parameter.
+///
+/// In the configuration, a plain string is a regex. The other matchers are written as a table, e.g.
+/// `{ exact = "A.1" }`, `{ not_regex = "^X" }`, `{ gte = 1, lt = 100 }`, `{ json = { ARTNR = "A1" } }`, `{ absent = true }`
+/// or `{ any = true }`.
+/// Exact values and regexes can be matched ignoring case by adding `case_insensitive = true`.
+#[derive(Debug, Clone)]
+pub enum ParameterMatcher {
+    /// The parameter must be sent with exactly this value. Unlike a regex, no characters have to be escaped.
+    Exact(String),
+    /// The parameter must be sent with this value, ignoring the case.
+    ExactIgnoreCase(String),
+    /// The parameter must be sent with a value matching this regex.
+    Regex(DeserializedRegex),
+    /// The parameter must be sent with a value that doesn't match this regex.
+    NotRegex(DeserializedRegex),
+    /// The parameter must be sent with a number in this range. Both `1.5` and `1,5` are understood.
+    Numeric(NumericRange),
+    /// The parameter must be sent with JSON equal to this value. Whitespace and the order of object keys don't
+    /// matter, numbers are compared by their value.
+    Json(serde_json::Value),
+    /// The parameter must not be sent.
+    Absent,
+    /// The parameter must be sent, with any value.
+    Any,
+}
+
+/// The bounds of a [numeric parameter matcher][ParameterMatcher::Numeric]. All given bounds have to hold.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct NumericRange {
+    /// The number has to be greater than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gt: Option<f64>,
+    /// The number has to be greater than or equal to this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gte: Option<f64>,
+    /// The number has to be less than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lt: Option<f64>,
+    /// The number has to be less than or equal to this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lte: Option<f64>,
+}
+
+impl NumericRange {
+    /// Returns whether the value is a number within the bounds.
+    pub fn contains(&self, value: &str) -> bool {
+        let Ok(number) = value.trim().replace(',', ".").parse::<f64>() else {
+            return false;
         };
+        self.gt.is_none_or(|gt| number > gt)
+            && self.gte.is_none_or(|gte| number >= gte)
+            && self.lt.is_none_or(|lt| number < lt)
+            && self.lte.is_none_or(|lte| number <= lte)
     }
+}
 
-    #[test]
-    fn default_config() {
-        let config = super::AppConfig::new().unwrap();
-        assert_eq!(config.mock_resources.is_empty(), true);
-        assert_eq!(config.debug, false);
-        assert_eq!(config.webware.credentials.service_pass.len(), 32);
-        assert_eq!(config.webware.credentials.application_id.len(), 32);
-        assert_eq!(config.webware.webservices.vendor_hash.len(), 32);
-        assert_eq!(config.webware.webservices.application_hash.len(), 32);
-        assert_eq!(config.webware.webservices.version, 1);
-        assert_eq!(config.webware.webservices.application_secret, "1".to_string());
+impl Display for NumericRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bounds = [(">", self.gt), (">=", self.gte), ("<", self.lt), ("<=", self.lte)]
+            .into_iter()
+            .filter_map(|(operator, bound)| bound.map(|bound| format!("{} {}", operator, bound)))
+            .collect::<Vec<_>>();
+        write!(f, "{}", bounds.join(" and "))
     }
+}
 
-    #[test]
-    fn config_from_file() {
-        figment::Jail::expect_with(|jail| {
-            jail.create_file("test-config.toml", r#"[server]
-            bind_address = "0.0.0.0:3000"
-            
-            [[mock_resources]]
-            data_source.type = "Empty"
-            function = "ARTIKEL"
-            method = "INSERT"
-            revision = 1
-            parameters.ARTNR = "MeinArtikel""#)?;
+impl ParameterMatcher {
+    /// Creates a matcher for exactly the given value.
+    pub fn exact(value: &str) -> Self {
+        ParameterMatcher::Exact(value.to_string())
+    }
 
-            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
-            assert_eq!(config.server.unwrap().bind_address, "0.0.0.0:3000");
-            assert_eq!(config.mock_resources.len(), 1);
-            assert_eq!(config.mock_resources[0].function, "ARTIKEL");
-            assert_eq!(config.mock_resources[0].method, super::MockResourceMethod::Insert);
-            assert_eq!(config.mock_resources[0].revision, 1);
-            assert_eq!(config.mock_resources[0].parameters.as_ref().unwrap().get("ARTNR").unwrap().is_match("MeinArtikel"), true);
+    /// Creates a matcher for the given regex.
+    pub fn regex(regex: &str) -> Result<Self, regex::Error> {
+        DeserializedRegex::new(regex).map(ParameterMatcher::Regex)
+    }
 
-            Ok(())
-        });
+    /// Creates a matcher for values that don't match the given regex.
+    pub fn not_regex(regex: &str) -> Result<Self, regex::Error> {
+        DeserializedRegex::new(regex).map(ParameterMatcher::NotRegex)
     }
 
-    one_line_assert_eq!(method_get_to_string, super::MockResourceMethod::Get.to_string(), "GET");
-    one_line_assert_eq!(method_insert_to_string, super::MockResourceMethod::Insert.to_string(), "INSERT");
-    one_line_assert_eq!(method_put_to_string, super::MockResourceMethod::Put.to_string(), "PUT");
-    one_line_assert_eq!(method_delete_to_string, super::MockResourceMethod::Delete.to_string(), "DELETE");
-    one_line_assert_eq!(method_exec_to_string, super::MockResourceMethod::Exec.to_string(), "EXEC");
-    one_line_assert_eq!(mock_resource_without_params_to_string, super::MockResource {
-        data_source: super::FileOrString::File {
-            file: "data/artikel_clean.json".to_string(),
-        },
-        function: "ARTIKEL".to_string(),
-        method: super::MockResourceMethod::Get,
-        revision: 3,
-        parameters: None,
-    }.to_string(), "MockResource { function: ARTIKEL, method: GET, revision: 3, parameters: None }");
-    one_line_assert_eq!(mock_resource_with_params_to_string, super::MockResource {
-        data_source: super::FileOrString::File {
-            file: "data/artikel_art_nr_clean.json".to_string(),
-        },
-        function: "ARTIKEL".to_string(),
-        method: super::MockResourceMethod::Get,
-        revision: 3,
-        parameters: Some(wwsvc_rs::collection! {
-            "FELDER".to_string() => DeserializedRegex(regex::Regex::new("ART_1_25").unwrap()),
+    /// Creates a matcher for JSON equal to the given value.
+    pub fn json(value: serde_json::Value) -> Self {
+        ParameterMatcher::Json(value)
+    }
+
+    /// Makes the matcher ignore the case, if it is an exact value or a regex.
+    pub fn ignore_case(self) -> Result<Self, regex::Error> {
+        Ok(match self {
+            ParameterMatcher::Exact(exact) => ParameterMatcher::ExactIgnoreCase(exact),
+            ParameterMatcher::Regex(regex) => ParameterMatcher::Regex(case_insensitive(&regex)?),
+            ParameterMatcher::NotRegex(regex) => ParameterMatcher::NotRegex(case_insensitive(&regex)?),
+            matcher => matcher,
         })
+    }
+
+    /// Returns whether a sent value matches. [Absent][ParameterMatcher::Absent] never matches a sent value.
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            ParameterMatcher::Exact(exact) => exact == value,
+            ParameterMatcher::ExactIgnoreCase(exact) => exact.to_lowercase() == value.to_lowercase(),
+            ParameterMatcher::Regex(regex) => regex.is_match(value),
+            ParameterMatcher::NotRegex(regex) => !regex.is_match(value),
+            ParameterMatcher::Numeric(range) => range.contains(value),
+            ParameterMatcher::Json(expected) => serde_json::from_str(value)
+                .map(|value| json_eq(expected, &value))
+                .unwrap_or(false),
+            ParameterMatcher::Absent => false,
+            ParameterMatcher::Any => true,
+        }
+    }
+
+    /// Returns whether the values sent for the parameter match, `values` being empty if it wasn't sent.
+    pub fn matches(&self, values: &[&str]) -> bool {
+        match self {
+            ParameterMatcher::Absent => values.is_empty(),
+            _ => values.iter().any(|value| self.is_match(value)),
+        }
+    }
+}
+
+/// Compares JSON values, treating numbers as equal if they have the same value, e.g. `1` and `1.0`.
+fn json_eq(expected: &serde_json::Value, actual: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    match (expected, actual) {
+        (Value::Number(expected), Value::Number(actual)) => expected.as_f64() == actual.as_f64(),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len() && expected.iter().zip(actual).all(|(expected, actual)| json_eq(expected, actual))
+        }
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .all(|(key, expected)| actual.get(key).is_some_and(|actual| json_eq(expected, actual)))
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+/// Prefixes the regex with `(?i)`, so it still ignores the case when it is serialized.
+fn case_insensitive(regex: &DeserializedRegex) -> Result<DeserializedRegex, regex::Error> {
+    match regex.as_str().starts_with("(?i)") {
+        true => Ok(regex.clone()),
+        false => DeserializedRegex::new(&format!("(?i){}", regex.as_str())),
+    }
+}
+
+impl Display for ParameterMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParameterMatcher::Exact(exact) => write!(f, "{:?}", exact),
+            ParameterMatcher::ExactIgnoreCase(exact) => write!(f, "{:?} (ignoring case)", exact),
+            ParameterMatcher::Regex(regex) => write!(f, "/{}/", regex.as_str()),
+            ParameterMatcher::NotRegex(regex) => write!(f, "not /{}/", regex.as_str()),
+            ParameterMatcher::Numeric(range) => write!(f, "{}", range),
+            ParameterMatcher::Json(value) => write!(f, "JSON {}", value),
+            ParameterMatcher::Absent => write!(f, "absent"),
+            ParameterMatcher::Any => write!(f, "any value"),
+        }
+    }
+}
+
+impl From<DeserializedRegex> for ParameterMatcher {
+    fn from(regex: DeserializedRegex) -> Self {
+        ParameterMatcher::Regex(regex)
+    }
+}
+
+impl From<NumericRange> for ParameterMatcher {
+    fn from(range: NumericRange) -> Self {
+        ParameterMatcher::Numeric(range)
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ParameterMatcherTable {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exact: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regex: Option<DeserializedRegex>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_regex: Option<DeserializedRegex>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    absent: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    any: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    case_insensitive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gt: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gte: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lt: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lte: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawParameterMatcher {
+    Regex(DeserializedRegex),
+    Table(ParameterMatcherTable),
+}
+
+impl<'de> Deserialize<'de> for ParameterMatcher {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let table = match RawParameterMatcher::deserialize(deserializer)? {
+            RawParameterMatcher::Regex(regex) => return Ok(ParameterMatcher::Regex(regex)),
+            RawParameterMatcher::Table(table) => table,
+        };
+
+        let range = NumericRange {
+            gt: table.gt,
+            gte: table.gte,
+            lt: table.lt,
+            lte: table.lte,
+        };
+        let numeric = range != NumericRange::default();
+        let kinds = [
+            table.exact.is_some(),
+            table.regex.is_some(),
+            table.not_regex.is_some(),
+            table.json.is_some(),
+            table.absent,
+            table.any,
+            numeric,
+        ];
+        if kinds.iter().filter(|kind| **kind).count() != 1 {
+            return Err(serde::de::Error::custom(
+                "A parameter matcher needs exactly one of exact, regex, not_regex, gt/gte/lt/lte, json, absent = true or any = true",
+            ));
+        }
+
+        let matcher = if let Some(exact) = table.exact {
+            ParameterMatcher::Exact(exact)
+        } else if let Some(regex) = table.regex {
+            ParameterMatcher::Regex(regex)
+        } else if let Some(regex) = table.not_regex {
+            ParameterMatcher::NotRegex(regex)
+        } else if numeric {
+            ParameterMatcher::Numeric(range)
+        } else if let Some(json) = table.json {
+            ParameterMatcher::Json(json)
+        } else if table.absent {
+            ParameterMatcher::Absent
+        } else {
+            ParameterMatcher::Any
+        };
+
+        match table.case_insensitive {
+            true if matches!(matcher, ParameterMatcher::Exact(_) | ParameterMatcher::Regex(_) | ParameterMatcher::NotRegex(_)) => {
+                matcher.ignore_case().map_err(serde::de::Error::custom)
+            }
+            true => Err(serde::de::Error::custom("case_insensitive only applies to exact, regex and not_regex")),
+            false => Ok(matcher),
+        }
+    }
+}
+
+impl Serialize for ParameterMatcher {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let table = match self.clone() {
+            ParameterMatcher::Regex(regex) => return RawParameterMatcher::Regex(regex).serialize(serializer),
+            ParameterMatcher::Exact(exact) => ParameterMatcherTable { exact: Some(exact), ..Default::default() },
+            ParameterMatcher::ExactIgnoreCase(exact) => ParameterMatcherTable { exact: Some(exact), case_insensitive: true, ..Default::default() },
+            ParameterMatcher::NotRegex(regex) => ParameterMatcherTable { not_regex: Some(regex), ..Default::default() },
+            ParameterMatcher::Numeric(range) => ParameterMatcherTable {
+                gt: range.gt,
+                gte: range.gte,
+                lt: range.lt,
+                lte: range.lte,
+                ..Default::default()
+            },
+            ParameterMatcher::Json(json) => ParameterMatcherTable { json: Some(json), ..Default::default() },
+            ParameterMatcher::Absent => ParameterMatcherTable { absent: true, ..Default::default() },
+            ParameterMatcher::Any => ParameterMatcherTable { any: true, ..Default::default() },
+        };
+        table.serialize(serializer)
+    }
+}
+
+fn default_bereich() -> Option<String> {
+    Some("WWSVC".to_string())
+}
+
+/// A custom COMRESULT for a mock resource, used to mock errors exactly as the WEBWARE returns them.
+///
+/// The `STATUS` is also used as the HTTP status code of the response.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ComResultConfig {
+    /// The `STATUS` of the COMRESULT, e.g. `400`.
+    pub status: u32,
+    /// The `CODE` of the COMRESULT, e.g. `400 Bad Request`.
+    ///
+    /// If not provided, the code is derived from the status.
+    #[serde(default)]
+    pub code: Option<String>,
+    /// The `INFO` of the COMRESULT.
+    pub info: String,
+    /// The `INFO2` of the COMRESULT.
+    #[serde(default)]
+    pub info2: Option<String>,
+    /// The `INFO3` of the COMRESULT.
+    #[serde(default)]
+    pub info3: Option<String>,
+    /// The `ERRNO` of the COMRESULT.
+    #[serde(default)]
+    pub errno: Option<String>,
+    /// The `ERRNOTXT` of the COMRESULT.
+    #[serde(default)]
+    pub errnotxt: Option<String>,
+    /// The `BEREICH` of the COMRESULT.
+    ///
+    /// If not provided, the `BEREICH` will be set to `WWSVC`.
+    #[serde(default = "default_bereich")]
+    pub bereich: Option<String>,
+}
+
+impl ComResultConfig {
+    /// Returns the `CODE` of the COMRESULT, deriving it from the status if it wasn't provided.
+    pub fn code(&self) -> String {
+        match &self.code {
+            Some(code) => code.clone(),
+            None => {
+                let reason = u16::try_from(self.status)
+                    .ok()
+                    .and_then(|status| axum::http::StatusCode::from_u16(status).ok())
+                    .and_then(|status| status.canonical_reason());
+                match reason {
+                    Some(reason) => format!("{} {}", self.status, reason),
+                    None => self.status.to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// Random jitter that is added to the latency of a response.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Jitter {
+    /// A delay that is uniformly distributed between `min_ms` and `max_ms` (both inclusive).
+    Uniform {
+        /// The minimum delay in milliseconds.
+        min_ms: u64,
+        /// The maximum delay in milliseconds.
+        max_ms: u64,
+    },
+    /// A normally distributed delay. Negative delays are treated as no delay.
+    Normal {
+        /// The mean delay in milliseconds.
+        mean_ms: f64,
+        /// The standard deviation of the delay in milliseconds.
+        std_dev_ms: f64,
+    },
+}
+
+impl Jitter {
+    /// Samples a delay from the distribution.
+    pub fn sample(&self) -> Duration {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        match *self {
+            Jitter::Uniform { min_ms, max_ms } => {
+                Duration::from_millis(rng.gen_range(min_ms.min(max_ms)..=max_ms.max(min_ms)))
+            }
+            Jitter::Normal { mean_ms, std_dev_ms } => {
+                // Box-Muller transform, so we don't need another dependency for a single distribution
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                Duration::from_secs_f64((mean_ms + z * std_dev_ms).max(0.0) / 1000.0)
+            }
+        }
+    }
+}
+
+/// The latency of responses, used to simulate a slow WEBWARE.
+///
+/// The delay of a response is the sum of the fixed delay, the jitter and, for the first call, the first call delay.
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
+pub struct LatencyConfig {
+    /// A fixed delay in milliseconds. Defaults to `0`.
+    #[serde(default)]
+    pub fixed_ms: u64,
+    /// Random jitter that is added to the delay. See [Jitter] for more information.
+    #[serde(default)]
+    pub jitter: Option<Jitter>,
+    /// An additional delay in milliseconds for the first call, e.g. to simulate a cold cache.
+    ///
+    /// For mock resources, the first call is counted per resource. For REGISTER and DEREGISTER, it is counted per route.
+    #[serde(default)]
+    pub first_call_ms: Option<u64>,
+}
+
+impl LatencyConfig {
+    /// Computes the delay of a response.
+    pub fn delay(&self, first_call: bool) -> Duration {
+        let mut delay = Duration::from_millis(self.fixed_ms);
+        if let Some(jitter) = &self.jitter {
+            delay += jitter.sample();
+        }
+        if first_call {
+            delay += Duration::from_millis(self.first_call_ms.unwrap_or_default());
+        }
+        delay
+    }
+}
+
+/// A fault that can be injected into a response.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum FaultKind {
+    /// A WEBWARE-style `500 Internal Server Error` COMRESULT.
+    InternalServerError,
+    /// A WEBWARE-style `503 Service Unavailable` COMRESULT.
+    ServiceUnavailable,
+    /// A raw HTML error page, like the ones returned by IIS or Apache, instead of JSON.
+    ErrorPage,
+    /// The regular response, but with the JSON body cut off in the middle.
+    TruncatedJson,
+    /// The connection is closed before the response body is sent.
+    DropConnection,
+}
+
+impl FaultKind {
+    fn all() -> Vec<FaultKind> {
+        vec![
+            FaultKind::InternalServerError,
+            FaultKind::ServiceUnavailable,
+            FaultKind::ErrorPage,
+            FaultKind::TruncatedJson,
+            FaultKind::DropConnection,
+        ]
+    }
+}
+
+/// The faults to randomly inject into responses.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FaultConfig {
+    /// The probability of a response being replaced by a fault, between `0.0` and `1.0`.
+    pub probability: f64,
+    /// The kinds of faults to choose from, with equal probability. See [FaultKind] for more information.
+    ///
+    /// If not provided, all kinds of faults are used.
+    #[serde(default = "FaultKind::all")]
+    pub kinds: Vec<FaultKind>,
+}
+
+/// What a [ResponseSequence] returns once all of its responses have been returned.
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
+pub enum SequenceMode {
+    /// Keep returning the last response.
+    #[default]
+    RepeatLast,
+    /// Start over with the first response.
+    Cycle,
+}
+
+/// An ordered list of responses that a mock resource returns on successive calls.
+///
+/// The first matching call returns the first response, the second call the second response and so on.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ResponseSequence {
+    /// The responses to return, in order.
+    pub responses: Vec<FileOrString>,
+    /// What to return once all responses have been returned. See [SequenceMode] for more information.
+    #[serde(default)]
+    pub mode: SequenceMode,
+}
+
+impl ResponseSequence {
+    /// Returns the response for the given call, starting at `0`, or `None` if there are no responses.
+    pub fn response(&self, call: usize) -> Option<&FileOrString> {
+        if self.responses.is_empty() {
+            return None;
+        }
+
+        let index = match self.mode {
+            SequenceMode::RepeatLast => call.min(self.responses.len() - 1),
+            SequenceMode::Cycle => call % self.responses.len(),
+        };
+        self.responses.get(index)
+    }
+}
+
+/// A single request/response pair of a [Cassette].
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CassetteInteraction {
+    /// The function name, without the method. For example, `AUFTRAG`.
+    pub function: String,
+    /// The method of the request. See [MockResourceMethod] for more information.
+    pub method: MockResourceMethod,
+    /// The revision(s) the request may use. See [MockResourceRevision] for more information.
+    pub revision: MockResourceRevision,
+    /// The parameters of the request. The request must have exactly these parameters and each value must match its regex.
+    #[serde(default)]
+    pub parameters: HashMap<String, DeserializedRegex>,
+    /// The [data source][FileOrString] to respond with.
+    #[serde(default = "FileOrString::empty")]
+    pub response: FileOrString,
+    /// An optional custom COMRESULT to respond with. See [ComResultConfig] for more information.
+    ///
+    /// If not provided, the interaction responds with `200 OK`.
+    #[serde(default)]
+    pub comresult: Option<ComResultConfig>,
+}
+
+impl Display for CassetteInteraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{} (Revision {}) {}",
+            self.function,
+            self.method,
+            self.revision,
+            serde_json::to_string(&self.parameters.iter().collect::<BTreeMap<_, _>>()).unwrap()
+        )
+    }
+}
+
+/// An ordered list of interactions that the server replays strictly in order, e.g. to test a workflow like "create order,
+/// add positions, book" deterministically.
+///
+/// Requests for functions that appear in the cassette must follow it exactly. If a request deviates from the next
+/// interaction, the server responds with an error and logs the deviation. Requests for other functions are answered
+/// by the mock resources as usual.
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct Cassette {
+    /// A TOML or JSON file with an `interactions` list to load the interactions from, instead of listing them inline.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// The interactions, in the order they are expected.
+    #[serde(default)]
+    pub interactions: Vec<CassetteInteraction>,
+}
+
+impl Cassette {
+    /// Returns the interactions of the cassette, reading them from the [file][Cassette::file] if one is given.
+    pub fn load_interactions(&self) -> anyhow::Result<Vec<CassetteInteraction>> {
+        let Some(file) = &self.file else {
+            return Ok(self.interactions.clone());
+        };
+        if !self.interactions.is_empty() {
+            anyhow::bail!("A cassette can either have a file or inline interactions, not both");
+        }
+
+        let cassette: Cassette = if file.ends_with(".json") {
+            let contents = std::fs::read_to_string(file)
+                .map_err(|err| anyhow::anyhow!("Failed to read {}: {}", file, err))?;
+            serde_json::from_str(&contents)?
+        } else {
+            Figment::from(Toml::file_exact(file)).extract()?
+        };
+        Ok(cassette.interactions)
+    }
+}
+
+fn default_system_fields() -> Vec<String> {
+    vec!["SNR".to_string()]
+}
+
+fn default_max_lines_parameter() -> String {
+    "MAXLINES".to_string()
+}
+
+fn default_start_parameter() -> String {
+    "STARTPOS".to_string()
+}
+
+/// Treats the data of a GET resource as a full dataset and processes it per request like the WEBWARE does, so one
+/// data file serves every request.
+///
+/// The data has to be a list response like `{"ARTIKELLISTE": {"ANZAHL": "396", "ARTIKEL": [...]}}`. If the request
+/// contains `FELDER`, e.g. `ART_1_25,ART_51_60`, only these fields and the [system fields][DatasetConfig::system_fields]
+/// are returned per record. Asking for a field that no record has is answered with an error.
+///
+/// Request parameters can narrow down the records with [filters][DatasetConfig::filters], in which case the `ANZAHL`
+/// of the list is recomputed.
+///
+/// Lists can be paged through with the [max lines][DatasetConfig::max_lines_parameter] and
+/// [start][DatasetConfig::start_parameter] parameters, or with a cursor like the WEBWARE does: the client sends
+/// `WWSVC-CURSOR: CREATE` along with `WWSVC-ACCEPT-RESULT-MAX-LINES`, and the server answers with the first page and
+/// a cursor ID in the `WWSVC-CURSOR` header. Sending that ID returns the next page, until the server answers with
+/// `CLOSED`.
+///
+/// The parameters handled by the dataset don't have to be listed in the [parameters][MockResource::parameters] of the
+/// resource.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DatasetConfig {
+    /// The fields that are always returned, regardless of `FELDER`. Defaults to `["SNR"]`.
+    #[serde(default = "default_system_fields")]
+    pub system_fields: Vec<String>,
+    /// The filters by request parameter name, e.g. `ARTNR`. See [FieldFilter] for more information.
+    ///
+    /// Only records matching all filters of the sent parameters are returned.
+    #[serde(default)]
+    pub filters: HashMap<String, FieldFilter>,
+    /// The parameter limiting the number of records to return. Defaults to `MAXLINES`.
+    #[serde(default = "default_max_lines_parameter")]
+    pub max_lines_parameter: String,
+    /// The parameter selecting the position of the first record to return, starting at `1`. Defaults to `STARTPOS`.
+    #[serde(default = "default_start_parameter")]
+    pub start_parameter: String,
+}
+
+impl Default for DatasetConfig {
+    fn default() -> Self {
+        DatasetConfig {
+            system_fields: default_system_fields(),
+            filters: HashMap::new(),
+            max_lines_parameter: default_max_lines_parameter(),
+            start_parameter: default_start_parameter(),
+        }
+    }
+}
+
+impl DatasetConfig {
+    /// Adds a filter for the given request parameter, see [FieldFilter].
+    pub fn with_filter(mut self, parameter: &str, filter: FieldFilter) -> Self {
+        self.filters.insert(parameter.to_string(), filter);
+        self
+    }
+
+    /// Returns whether the parameter is handled by the dataset instead of the parameter matchers.
+    pub(crate) fn handles_parameter(&self, name: &str) -> bool {
+        name == crate::dataset::FIELDS_PARAMETER
+            || name == self.max_lines_parameter
+            || name == self.start_parameter
+            || self.filters.contains_key(name)
+    }
+}
+
+/// How a [FieldFilter] compares the parameter value with the field of a record.
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
+pub enum FilterMode {
+    /// The field has to be equal to the value.
+    #[default]
+    Equals,
+    /// The field has to start with the value.
+    Prefix,
+    /// The field has to match the value, in which `*` stands for any number of characters, e.g. `A*1`.
+    Wildcard,
+    /// The field has to be greater than or equal to the value, e.g. for `..._VON` parameters.
+    Min,
+    /// The field has to be less than or equal to the value, e.g. for `..._BIS` parameters.
+    Max,
+}
+
+/// A filter that narrows down the records of a [dataset][DatasetConfig] by a request parameter.
+///
+/// [Min][FilterMode::Min] and [Max][FilterMode::Max] compare numbers (`1,5` or `1.5`) and dates (`31.12.2024`) by
+/// their value, anything else alphabetically.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FieldFilter {
+    /// The field of the records to compare, e.g. `ART_1_25`.
+    pub field: String,
+    /// How the parameter value is compared with the field. Defaults to [FilterMode::Equals].
+    #[serde(default)]
+    pub mode: FilterMode,
+    /// Whether to ignore the case when comparing. Defaults to `false`.
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+impl FieldFilter {
+    /// Creates a filter comparing the given field in the given mode.
+    pub fn new(field: &str, mode: FilterMode) -> Self {
+        FieldFilter {
+            field: field.to_string(),
+            mode,
+            case_insensitive: false,
+        }
+    }
+
+    /// Ignores the case when comparing.
+    pub fn ignore_case(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+}
+
+/// The contract of a function, e.g. `ARTIKEL.GET` in revision 3, like the WEBWARE documents it.
+///
+/// Requests for the function are rejected with a WEBWARE-style `400` COMRESULT if a
+/// [required parameter][FunctionSchema::required_parameters] is missing, a parameter isn't
+/// [allowed][FunctionSchema::allowed_parameters] or a value doesn't fit its [type][FunctionSchema::parameters].
+///
+/// When the server starts, the data of the mock resources for the function is checked against the
+/// [fields][FunctionSchema::fields], so fixtures can't drift from the real API.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FunctionSchema {
+    /// The function name, without the method. For example, `ARTIKEL`.
+    pub function: String,
+    /// The method of the function. See [MockResourceMethod] for more information.
+    pub method: MockResourceMethod,
+    /// The revision(s) the schema describes. Defaults to every revision.
+    #[serde(default)]
+    pub revision: MockResourceRevision,
+    /// The parameters every request has to send.
+    #[serde(default)]
+    pub required_parameters: Vec<String>,
+    /// The parameters requests may send, in addition to the required and typed ones. If not provided, any parameters
+    /// are allowed.
+    #[serde(default)]
+    pub allowed_parameters: Option<Vec<String>>,
+    /// The types of the parameters by name.
+    #[serde(default)]
+    pub parameters: HashMap<String, FieldSchema>,
+    /// The types of the fields in responses by name, e.g. `ART_1_25`.
+    #[serde(default)]
+    pub fields: HashMap<String, FieldSchema>,
+}
+
+impl FunctionSchema {
+    /// Returns whether the schema describes the given function, method and revision.
+    pub fn describes(&self, function: &str, method: &MockResourceMethod, revision: u32) -> bool {
+        self.function.eq_ignore_ascii_case(function) && self.method == *method && self.revision.matches(revision)
+    }
+
+    /// Returns whether the schema describes the function of the mock resource in any of its revisions.
+    pub fn describes_resource(&self, resource: &MockResource) -> bool {
+        self.function.eq_ignore_ascii_case(&resource.function)
+            && self.method == resource.method
+            && self.revision.overlaps(&resource.revision)
+    }
+
+    /// Returns whether requests may send the parameter.
+    pub fn allows_parameter(&self, name: &str) -> bool {
+        self.allowed_parameters
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|allowed| allowed == name))
+            || self.required_parameters.iter().any(|required| required == name)
+            || self.parameters.contains_key(name)
+    }
+}
+
+/// The type of a [field or parameter][FieldSchema]. The WEBWARE sends every value as a string.
+#[derive(Deserialize, Serialize, Default, Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    /// Any text.
+    #[default]
+    String,
+    /// A whole number, e.g. `-42`.
+    Integer,
+    /// A decimal number, e.g. `1,5` or `1.5`.
+    Decimal,
+    /// A date, e.g. `31.12.2024`.
+    Date,
+}
+
+/// The type and maximum length of a field or parameter of a [FunctionSchema].
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct FieldSchema {
+    /// The type of the value. Defaults to [FieldType::String].
+    #[serde(default, rename = "type")]
+    pub field_type: FieldType,
+    /// The maximum number of characters, e.g. `25` for `ART_1_25`.
+    #[serde(default)]
+    pub max_length: Option<usize>,
+}
+
+impl FieldSchema {
+    /// Checks the value against the type and length, returning why it doesn't fit.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        if let Some(max_length) = self.max_length {
+            if value.chars().count() > max_length {
+                return Err(format!("longer than {} characters", max_length));
+            }
+        }
+
+        let valid = match self.field_type {
+            FieldType::String => true,
+            FieldType::Integer => value.trim().parse::<i64>().is_ok(),
+            FieldType::Decimal => value.trim().replace(',', ".").parse::<f64>().is_ok_and(f64::is_finite),
+            FieldType::Date => crate::dataset::parse_date(value).is_some(),
+        };
+        match (valid, self.field_type) {
+            (true, _) => Ok(()),
+            (false, FieldType::Integer) => Err("not an integer".to_string()),
+            (false, FieldType::Decimal) => Err("not a number".to_string()),
+            (false, _) => Err("not a date".to_string()),
+        }
+    }
+}
+
+/// The schemas in a [schema file][AppConfig::schema_files].
+#[derive(Deserialize)]
+struct SchemaFile {
+    #[serde(default)]
+    schemas: Vec<FunctionSchema>,
+}
+
+/// A mock resource that the server will use to mock the WEBSERVICES.
+/// 
+/// The resource will only return the data from the data source if the function, method, revision and parameters match.
+/// Revisions can be matched exactly, by range or not at all, see [MockResourceRevision].
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MockResource {
+    /// The [data source][FileOrString] for the mock resource.
+    ///
+    /// This is ignored if the resource has a [sequence][MockResource::sequence] of responses.
+    #[serde(default = "FileOrString::empty")]
+    pub data_source: FileOrString,
+    /// The function name for the mock resource.
+    /// 
+    /// This is the name of the function but without the method. For example, `ARTIKEL`.
+    pub function: String,
+    /// The method for the mock resource. See [MockResourceMethod] for more information.
+    pub method: MockResourceMethod,
+    /// The revision(s) for the mock resource. See [MockResourceRevision] for more information.
+    pub revision: MockResourceRevision,
+
+    /// The parameters for the mock resource, each with a [matcher][ParameterMatcher].
+    ///
+    /// Parameters that aren't listed may be sent as well, unless [strict_parameters][MockResource::strict_parameters]
+    /// is enabled. If not provided, the request must not have any parameters.
+    pub parameters: Option<HashMap<String, ParameterMatcher>>,
+    /// Whether requests may only send the listed [parameters][MockResource::parameters]. Defaults to `false`, so any
+    /// additional parameters are allowed.
+    #[serde(default)]
+    pub strict_parameters: bool,
+    /// Processes the data of a GET resource as a full dataset, e.g. to handle `FELDER`. See [DatasetConfig] for more
+    /// information.
+    #[serde(default)]
+    pub dataset: Option<DatasetConfig>,
+    /// An optional sequence of responses to return on successive calls instead of the data source.
+    /// See [ResponseSequence] for more information.
+    #[serde(default)]
+    pub sequence: Option<ResponseSequence>,
+    /// An optional custom COMRESULT to respond with. See [ComResultConfig] for more information.
+    ///
+    /// If not provided, the resource responds with `200 OK`.
+    #[serde(default)]
+    pub comresult: Option<ComResultConfig>,
+    /// An optional latency for this resource, overriding the global [latency][AppConfig::latency].
+    #[serde(default)]
+    pub latency: Option<LatencyConfig>,
+    /// Optional faults for this resource, overriding the global [faults][AppConfig::faults].
+    #[serde(default)]
+    pub faults: Option<FaultConfig>,
+    /// Whether the data source is a template, in which placeholders like `{{param.ARTNR}}` are replaced
+    /// with values from the request. Defaults to `false`.
+    ///
+    /// The following placeholders are available:
+    ///
+    /// - `{{param.NAME}}`: the value of the request parameter `NAME`, or an empty string if it wasn't sent
+    /// - `{{function}}` and `{{revision}}`: the function name (including the method) and revision of the request
+    /// - `{{service_pass}}`, `{{app_hash}}` and `{{request_id}}`: the values from the `WWSVC_PASSINFO` block
+    /// - `{{call}}`: the number of calls to this resource so far, starting at `1`
+    /// - `{{now.date}}`, `{{now.time}}`, `{{now.iso}}` and `{{now.unix}}`: the current date (`16.10.2026`), time
+    ///   (`14:30:00`), timestamp (`2026-10-16T14:30:00Z`) or Unix timestamp, all in UTC
+    /// - `{{random.number}}` and `{{random.hash}}`: a random number or 32 character hexadecimal hash
+    ///
+    /// Values are escaped for use inside JSON strings.
+    #[serde(default)]
+    pub template: bool,
+}
+
+/// An empty GET resource for any revision of an unnamed function, without parameters. Useful as the base of a struct
+/// literal, e.g. `MockResource { function: "ARTIKEL".to_string(), ..Default::default() }`.
+impl Default for MockResource {
+    fn default() -> Self {
+        MockResource {
+            data_source: FileOrString::Empty,
+            function: String::new(),
+            method: MockResourceMethod::Get,
+            revision: MockResourceRevision::Any,
+            parameters: None,
+            strict_parameters: false,
+            dataset: None,
+            sequence: None,
+            comresult: None,
+            latency: None,
+            faults: None,
+            template: false,
+        }
+    }
+}
+
+impl MockResource {
+    /// Returns whether the parameter is handled by the [dataset][MockResource::dataset] of the resource, so it doesn't
+    /// have to be matched.
+    pub(crate) fn handles_parameter(&self, name: &str) -> bool {
+        self.method == MockResourceMethod::Get
+            && self.effective_dataset().is_some_and(|dataset| dataset.handles_parameter(name))
+    }
+
+    /// Returns the [dataset][MockResource::dataset] of the resource, which defaults to [DatasetConfig::default] for
+    /// [generated][FileOrString::Generated] data.
+    pub(crate) fn effective_dataset(&self) -> Option<Cow<'_, DatasetConfig>> {
+        match (&self.dataset, &self.data_source) {
+            (Some(dataset), _) => Some(Cow::Borrowed(dataset)),
+            (None, FileOrString::Generated { .. }) => Some(Cow::Owned(DatasetConfig::default())),
+            (None, _) => None,
+        }
+    }
+
+    /// Returns the data source to respond with for the given call to this resource, starting at `0`.
+    pub fn data_source_for_call(&self, call: usize) -> &FileOrString {
+        self.sequence
+            .as_ref()
+            .and_then(|sequence| sequence.response(call))
+            .unwrap_or(&self.data_source)
+    }
+}
+
+impl Display for MockResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MockResource {{ function: {}, method: {}, revision: {}, parameters: {} }}",
+            self.function, self.method, self.revision, match self.parameters {
+                Some(ref parameters) => serde_json::to_string(&parameters.iter().collect::<BTreeMap<_, _>>()).unwrap(),
+                None => "None".to_string(),
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+// figment::Error is large, but it is what figment::Jail returns
+#[allow(clippy::result_large_err)]
+mod tests {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::DeserializedRegex;
+
+    macro_rules! one_line_assert_eq {
+        ($fn:ident, $left:expr, $right:expr) => {
+            #[test]
+            fn $fn() {
+                assert_eq!($left, $right);
+            }
+        };
+    }
+
+    #[test]
+    fn default_config() {
+        let config = super::AppConfig::new().unwrap();
+        assert_eq!(config.mock_resources.is_empty(), true);
+        assert_eq!(config.debug, false);
+        assert_eq!(config.webware.authenticate_requests, false);
+        assert_eq!(config.webware.sessions.enabled, false);
+        assert_eq!(config.webware.credentials.service_pass.len(), 32);
+        assert_eq!(config.webware.credentials.application_id.len(), 32);
+        assert_eq!(config.webware.webservices.vendor_hash.len(), 32);
+        assert_eq!(config.webware.webservices.application_hash.len(), 32);
+        assert_eq!(config.webware.webservices.version, 1);
+        assert_eq!(config.webware.webservices.application_secret, "1".to_string());
+    }
+
+    #[test]
+    fn config_from_file() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[server]
+            bind_address = "0.0.0.0:3000"
+            
+            [[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "INSERT"
+            revision = 1
+            parameters.ARTNR = "MeinArtikel""#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            assert_eq!(config.server.unwrap().bind_address, "0.0.0.0:3000");
+            assert_eq!(config.mock_resources.len(), 1);
+            assert_eq!(config.mock_resources[0].function, "ARTIKEL");
+            assert_eq!(config.mock_resources[0].method, super::MockResourceMethod::Insert);
+            assert_eq!(config.mock_resources[0].revision, super::MockResourceRevision::Exact(1));
+            assert_eq!(config.mock_resources[0].parameters.as_ref().unwrap().get("ARTNR").unwrap().is_match("MeinArtikel"), true);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn revision_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = 3
+
+            [[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = "2-3"
+
+            [[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = { min = 4, max = 6 }
+
+            [[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = "any""#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            assert_eq!(config.mock_resources[0].revision, super::MockResourceRevision::Exact(3));
+            assert_eq!(config.mock_resources[1].revision, super::MockResourceRevision::Range { min: 2, max: 3 });
+            assert_eq!(config.mock_resources[2].revision, super::MockResourceRevision::Range { min: 4, max: 6 });
+            assert_eq!(config.mock_resources[3].revision, super::MockResourceRevision::Any);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn revision_matches() {
+        assert_eq!(super::MockResourceRevision::Exact(3).matches(3), true);
+        assert_eq!(super::MockResourceRevision::Exact(3).matches(2), false);
+        assert_eq!(super::MockResourceRevision::Range { min: 2, max: 3 }.matches(2), true);
+        assert_eq!(super::MockResourceRevision::Range { min: 2, max: 3 }.matches(4), false);
+        assert_eq!(super::MockResourceRevision::Any.matches(42), true);
+    }
+
+    #[test]
+    fn applications_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[webware.webservices]
+            application_hash = "default"
+
+            [[webware.applications]]
+            name = "shop"
+            webservices.application_hash = "shop"
+
+            [[webware.applications.mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = 3"#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            let applications = config.webware.all_applications();
+            assert_eq!(applications.len(), 2);
+            assert_eq!(applications[0].webservices.application_hash, "default");
+            assert_eq!(applications[0].mock_resources.is_none(), true);
+            assert_eq!(applications[1].name.as_deref(), Some("shop"));
+            assert_eq!(applications[1].webservices.application_hash, "shop");
+            assert_eq!(applications[1].credentials.service_pass.len(), 32);
+            assert_eq!(applications[1].mock_resources.as_ref().unwrap().len(), 1);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn stateful_function_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[[stateful_functions]]
+            function = "ARTIKEL"
+            key_field = "ART_1_25"
+            key_parameter = "ARTNR"
+            seed = { type = "File", file = "data/artikel.json" }
+
+            [[stateful_functions]]
+            function = "KUNDE"
+            key_field = "KUNDENNR"
+            list_name = "KUNDENLISTE""#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            let artikel = &config.stateful_functions[0];
+            assert_eq!(artikel.key_parameter(), "ARTNR");
+            assert_eq!(artikel.list_name(), "ARTIKELLISTE");
+            assert_eq!(artikel.item_name(), "ARTIKEL");
+            let kunde = &config.stateful_functions[1];
+            assert_eq!(kunde.key_parameter(), "KUNDENNR");
+            assert_eq!(kunde.list_name(), "KUNDENLISTE");
+            assert_eq!(matches!(kunde.seed, super::FileOrString::Empty), true);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn sequence_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[[mock_resources]]
+            function = "JOB"
+            method = "EXEC"
+            revision = 1
+            sequence.mode = "Cycle"
+            sequence.responses = [
+                { type = "String", value = "1" },
+                { type = "String", value = "2" },
+            ]"#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            let resource = &config.mock_resources[0];
+            assert_eq!(resource.sequence.as_ref().unwrap().mode, super::SequenceMode::Cycle);
+            assert_eq!(resource.data_source_for_call(0).as_string(), "1");
+            assert_eq!(resource.data_source_for_call(1).as_string(), "2");
+            assert_eq!(resource.data_source_for_call(2).as_string(), "1");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn comresult_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[[mock_resources]]
+            function = "ARTIKEL"
+            method = "INSERT"
+            revision = 1
+            comresult.status = 400
+            comresult.info = "Es ist ein Fehler aufgetreten."
+            comresult.info2 = "Artikel existiert bereits""#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            let comresult = config.mock_resources[0].comresult.as_ref().unwrap();
+            assert_eq!(comresult.status, 400);
+            assert_eq!(comresult.code(), "400 Bad Request");
+            assert_eq!(comresult.info2.as_deref(), Some("Artikel existiert bereits"));
+            assert_eq!(comresult.bereich.as_deref(), Some("WWSVC"));
+            assert_eq!(comresult.errno, None);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn latency_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"[latency]
+            fixed_ms = 100
+            jitter = { type = "Uniform", min_ms = 10, max_ms = 20 }
+
+            [[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = 1
+            latency.first_call_ms = 500
+            latency.jitter = { type = "Normal", mean_ms = 50.0, std_dev_ms = 10.0 }"#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            let latency = config.latency.unwrap();
+            assert_eq!(latency.fixed_ms, 100);
+            assert_eq!(latency.jitter, Some(super::Jitter::Uniform { min_ms: 10, max_ms: 20 }));
+            let delay = latency.delay(false).as_millis();
+            assert_eq!((110..=120).contains(&delay), true);
+
+            let latency = config.mock_resources[0].latency.as_ref().unwrap();
+            assert_eq!(latency.fixed_ms, 0);
+            assert_eq!(latency.first_call_ms, Some(500));
+            assert_eq!(latency.jitter, Some(super::Jitter::Normal { mean_ms: 50.0, std_dev_ms: 10.0 }));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn faults_from_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("test-config.toml", r#"seed = 42
+
+            [faults]
+            probability = 0.1
+
+            [[mock_resources]]
+            data_source.type = "Empty"
+            function = "ARTIKEL"
+            method = "GET"
+            revision = 1
+            faults = { probability = 1.0, kinds = ["TruncatedJson", "DropConnection"] }"#)?;
+
+            let config = super::AppConfig::from_file(std::path::Path::new("test-config.toml")).unwrap();
+            assert_eq!(config.seed, Some(42));
+            let faults = config.faults.unwrap();
+            assert_eq!(faults.probability, 0.1);
+            assert_eq!(faults.kinds.len(), 5);
+            let faults = config.mock_resources[0].faults.as_ref().unwrap();
+            assert_eq!(faults.kinds, vec![super::FaultKind::TruncatedJson, super::FaultKind::DropConnection]);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn latency_first_call() {
+        let latency = super::LatencyConfig {
+            fixed_ms: 10,
+            jitter: None,
+            first_call_ms: Some(90),
+        };
+        assert_eq!(latency.delay(true).as_millis(), 100);
+        assert_eq!(latency.delay(false).as_millis(), 10);
+    }
+
+    #[test]
+    fn parameter_matchers() {
+        let matchers: std::collections::BTreeMap<String, super::ParameterMatcher> = serde_json::from_str(
+            r#"{ "A": "^A", "B": { "exact": "B.1" }, "C": { "absent": true }, "D": { "any": true }, "E": { "regex": "^E" } }"#,
+        )
+        .unwrap();
+        assert_eq!(matchers["A"].matches(&["A1"]), true);
+        assert_eq!(matchers["B"].matches(&["B.1"]), true);
+        assert_eq!(matchers["B"].matches(&["BX1"]), false);
+        assert_eq!(matchers["C"].matches(&[]), true);
+        assert_eq!(matchers["C"].matches(&[""]), false);
+        assert_eq!(matchers["D"].matches(&[""]), true);
+        assert_eq!(matchers["D"].matches(&[]), false);
+        assert_eq!(matchers["E"].matches(&["E1"]), true);
+        assert_eq!(
+            serde_json::to_string(&matchers).unwrap(),
+            r#"{"A":"^A","B":{"exact":"B.1"},"C":{"absent":true},"D":{"any":true},"E":"^E"}"#
+        );
+
+        let invalid = serde_json::from_str::<super::ParameterMatcher>(r#"{ "exact": "A", "absent": true }"#);
+        assert_eq!(invalid.is_err(), true);
+    }
+
+    #[test]
+    fn richer_parameter_matchers() {
+        use figment::providers::{Format, Toml};
+
+        let figment = figment::Figment::from(Toml::string(
+            r#"
+            A = { exact = "a.1", case_insensitive = true }
+            B = { regex = "^ab", case_insensitive = true }
+            C = { not_regex = "^X" }
+            D = { gte = 1, lt = 10.5 }
+            E = { json = { ARTNR = "A1", MENGE = 2, LAGER = [1, 2] } }
+            "#,
+        ));
+        let matchers: std::collections::BTreeMap<String, super::ParameterMatcher> = figment.extract().unwrap();
+        assert_eq!(matchers["A"].is_match("A.1"), true);
+        assert_eq!(matchers["A"].is_match("AX1"), false);
+        assert_eq!(matchers["B"].is_match("ABC"), true);
+        assert_eq!(matchers["C"].is_match("Y1"), true);
+        assert_eq!(matchers["C"].is_match("X1"), false);
+        assert_eq!(matchers["C"].matches(&[]), false);
+        assert_eq!(matchers["D"].is_match("1"), true);
+        assert_eq!(matchers["D"].is_match("10,4"), true);
+        assert_eq!(matchers["D"].is_match("10.5"), false);
+        assert_eq!(matchers["D"].is_match("0.99"), false);
+        assert_eq!(matchers["D"].is_match("viel"), false);
+        assert_eq!(matchers["D"].to_string(), ">= 1 and < 10.5");
+        assert_eq!(matchers["E"].is_match(r#"{"LAGER": [1, 2.0], "MENGE": 2, "ARTNR": "A1"}"#), true);
+        assert_eq!(matchers["E"].is_match(r#"{"ARTNR": "A1", "MENGE": "2", "LAGER": [1, 2]}"#), false);
+        assert_eq!(matchers["E"].is_match(r#"{"ARTNR": "A1", "MENGE": 2, "LAGER": [2, 1]}"#), false);
+        assert_eq!(matchers["E"].is_match(r#"{"ARTNR": "A1", "MENGE": 2, "LAGER": [1, 2], "X": 1}"#), false);
+        assert_eq!(matchers["E"].is_match("kein JSON"), false);
+
+        assert_eq!(
+            serde_json::to_string(&matchers).unwrap(),
+            r#"{"A":{"exact":"a.1","case_insensitive":true},"B":"(?i)^ab","C":{"not_regex":"^X"},"D":{"gte":1.0,"lt":10.5},"E":{"json":{"ARTNR":"A1","LAGER":[1,2],"MENGE":2}}}"#
+        );
+
+        let invalid = serde_json::from_str::<super::ParameterMatcher>(r#"{ "any": true, "case_insensitive": true }"#);
+        assert_eq!(invalid.is_err(), true);
+        let invalid = serde_json::from_str::<super::ParameterMatcher>(r#"{ "exact": "A", "unknown": 1 }"#);
+        assert_eq!(invalid.is_err(), true);
+    }
+
+    #[test]
+    fn normal_jitter_is_never_negative() {
+        let jitter = super::Jitter::Normal { mean_ms: 0.0, std_dev_ms: 100.0 };
+        for _ in 0..100 {
+            assert_eq!(jitter.sample() >= std::time::Duration::ZERO, true);
+        }
+    }
+
+    #[test]
+    fn sequence_repeats_last() {
+        let sequence = super::ResponseSequence {
+            responses: vec![
+                super::FileOrString::String { value: "1".to_string() },
+                super::FileOrString::String { value: "2".to_string() },
+            ],
+            mode: super::SequenceMode::RepeatLast,
+        };
+        assert_eq!(sequence.response(0).unwrap().as_string(), "1");
+        assert_eq!(sequence.response(1).unwrap().as_string(), "2");
+        assert_eq!(sequence.response(5).unwrap().as_string(), "2");
+    }
+
+    one_line_assert_eq!(invalid_revision_range_from_str, super::MockResourceRevision::from_str("3-2").unwrap_err(), "Invalid revision range: 3-2");
+    one_line_assert_eq!(revision_star_from_str, super::MockResourceRevision::from_str("*").unwrap(), super::MockResourceRevision::Any);
+    one_line_assert_eq!(revision_range_to_string, super::MockResourceRevision::Range { min: 2, max: 3 }.to_string(), "2-3");
+    one_line_assert_eq!(method_get_to_string, super::MockResourceMethod::Get.to_string(), "GET");
+    one_line_assert_eq!(method_insert_to_string, super::MockResourceMethod::Insert.to_string(), "INSERT");
+    one_line_assert_eq!(method_put_to_string, super::MockResourceMethod::Put.to_string(), "PUT");
+    one_line_assert_eq!(method_delete_to_string, super::MockResourceMethod::Delete.to_string(), "DELETE");
+    one_line_assert_eq!(method_exec_to_string, super::MockResourceMethod::Exec.to_string(), "EXEC");
+    one_line_assert_eq!(mock_resource_without_params_to_string, super::MockResource {
+        data_source: super::FileOrString::File {
+            file: "data/artikel.json".to_string(),
+        },
+        function: "ARTIKEL".to_string(),
+        method: super::MockResourceMethod::Get,
+        revision: 3.into(),
+        parameters: None,
+        ..Default::default()
+    }.to_string(), "MockResource { function: ARTIKEL, method: GET, revision: 3, parameters: None }");
+    one_line_assert_eq!(mock_resource_with_params_to_string, super::MockResource {
+        data_source: super::FileOrString::File {
+            file: "data/artikel_art_nr.json".to_string(),
+        },
+        function: "ARTIKEL".to_string(),
+        method: super::MockResourceMethod::Get,
+        revision: 3.into(),
+        parameters: Some(wwsvc_rs::collection! {
+            "FELDER".to_string() => super::ParameterMatcher::Regex(DeserializedRegex(regex::Regex::new("ART_1_25").unwrap())),
+        }),
+        ..Default::default()
     }.to_string(), "MockResource { function: ARTIKEL, method: GET, revision: 3, parameters: {\"FELDER\":\"ART_1_25\"} }");
     one_line_assert_eq!(unknown_method_from_str, super::MockResourceMethod::from_str("UNKNOWN").unwrap_err(), "Unknown method: UNKNOWN");
     one_line_assert_eq!(empty_as_str, super::FileOrString::Empty.as_string(), "");
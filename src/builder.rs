@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    ComResultConfig, DatasetConfig, FaultConfig, FileOrString, GeneratedEntity, LatencyConfig, MockResource,
+    MockResourceMethod, MockResourceRevision, NumericRange, ParameterMatcher, ResponseSequence,
+};
+
+/// Starts building a [MockResource] for the given function, e.g. `ARTIKEL`.
+///
+/// ```rust,ignore
+/// let resource = mock("ARTIKEL")
+///     .method(MockResourceMethod::Get)
+///     .revision(3)
+///     .param("FELDER", "ART_1_25")
+///     .respond_with_file("data/artikel.json");
+/// ```
+pub fn mock(function: &str) -> MockResourceBuilder {
+    MockResourceBuilder::new(function)
+}
+
+/// A fluent builder for a [MockResource], created by [mock].
+///
+/// Unless configured otherwise, the resource answers `GET` requests of any revision. Like a resource without
+/// `parameters` in the configuration, it only matches requests without parameters until a parameter matcher is added.
+/// The `respond_with_*` methods finish the resource.
+#[derive(Debug, Clone)]
+pub struct MockResourceBuilder {
+    function: String,
+    method: MockResourceMethod,
+    revision: MockResourceRevision,
+    parameters: Option<HashMap<String, ParameterMatcher>>,
+    strict_parameters: bool,
+    dataset: Option<DatasetConfig>,
+    comresult: Option<ComResultConfig>,
+    latency: Option<LatencyConfig>,
+    faults: Option<FaultConfig>,
+    template: bool,
+}
+
+impl MockResourceBuilder {
+    /// Creates a builder for the given function, e.g. `ARTIKEL`.
+    pub fn new(function: &str) -> Self {
+        MockResourceBuilder {
+            function: function.to_string(),
+            method: MockResourceMethod::Get,
+            revision: MockResourceRevision::Any,
+            parameters: None,
+            strict_parameters: false,
+            dataset: None,
+            comresult: None,
+            latency: None,
+            faults: None,
+            template: false,
+        }
+    }
+
+    /// Sets the method, see [MockResourceMethod].
+    pub fn method(mut self, method: MockResourceMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets the revision(s), e.g. `3` or [MockResourceRevision::Range].
+    pub fn revision(mut self, revision: impl Into<MockResourceRevision>) -> Self {
+        self.revision = revision.into();
+        self
+    }
+
+    /// Adds a [matcher][ParameterMatcher] for the parameter with the given name.
+    pub fn param_matcher(mut self, name: &str, matcher: ParameterMatcher) -> Self {
+        self.parameters
+            .get_or_insert_with(HashMap::new)
+            .insert(name.to_string(), matcher);
+        self
+    }
+
+    /// Requires the parameter to be sent with exactly the given value.
+    pub fn param(self, name: &str, value: &str) -> Self {
+        self.param_matcher(name, ParameterMatcher::exact(value))
+    }
+
+    /// Requires the parameter to be sent with a value matching the regex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex is invalid.
+    pub fn param_regex(self, name: &str, regex: &str) -> Self {
+        let matcher = ParameterMatcher::regex(regex)
+            .unwrap_or_else(|err| panic!("Invalid regex for parameter {}: {}", name, err));
+        self.param_matcher(name, matcher)
+    }
+
+    /// Requires the parameter to be sent with a value that doesn't match the regex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex is invalid.
+    pub fn param_not_regex(self, name: &str, regex: &str) -> Self {
+        let matcher = ParameterMatcher::not_regex(regex)
+            .unwrap_or_else(|err| panic!("Invalid regex for parameter {}: {}", name, err));
+        self.param_matcher(name, matcher)
+    }
+
+    /// Requires the parameter to be sent with a number within the range, see [NumericRange].
+    pub fn param_numeric(self, name: &str, range: NumericRange) -> Self {
+        self.param_matcher(name, ParameterMatcher::Numeric(range))
+    }
+
+    /// Requires the parameter to be sent with JSON equal to the value, see [ParameterMatcher::Json].
+    pub fn param_json(self, name: &str, value: serde_json::Value) -> Self {
+        self.param_matcher(name, ParameterMatcher::Json(value))
+    }
+
+    /// Requires the parameter to be sent with the given value, ignoring the case.
+    pub fn param_ignore_case(self, name: &str, value: &str) -> Self {
+        self.param_matcher(name, ParameterMatcher::ExactIgnoreCase(value.to_string()))
+    }
+
+    /// Requires the parameter not to be sent.
+    pub fn param_absent(self, name: &str) -> Self {
+        self.param_matcher(name, ParameterMatcher::Absent)
+    }
+
+    /// Requires the parameter to be sent, with any value.
+    pub fn param_any(self, name: &str) -> Self {
+        self.param_matcher(name, ParameterMatcher::Any)
+    }
+
+    /// Allows any parameters. Without parameter matchers, only requests without parameters match.
+    pub fn any_params(mut self) -> Self {
+        self.parameters.get_or_insert_with(HashMap::new);
+        self
+    }
+
+    /// Rejects requests that send parameters without a matcher, see [MockResource::strict_parameters].
+    pub fn strict_params(mut self) -> Self {
+        self.strict_parameters = true;
+        self
+    }
+
+    /// Processes the data as a full dataset, e.g. to handle `FELDER`, see [DatasetConfig].
+    pub fn dataset(mut self, dataset: DatasetConfig) -> Self {
+        self.dataset = Some(dataset);
+        self
+    }
+
+    /// Responds with the given COMRESULT instead of `200 OK`, see [ComResultConfig].
+    pub fn comresult(mut self, comresult: ComResultConfig) -> Self {
+        self.comresult = Some(comresult);
+        self
+    }
+
+    /// Overrides the global latency for this resource, see [LatencyConfig].
+    pub fn latency(mut self, latency: LatencyConfig) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Overrides the global faults for this resource, see [FaultConfig].
+    pub fn faults(mut self, faults: FaultConfig) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Treats the data source as a template, see [MockResource::template].
+    pub fn template(mut self) -> Self {
+        self.template = true;
+        self
+    }
+
+    /// Finishes the resource with the given data source.
+    pub fn respond_with(self, data_source: FileOrString) -> MockResource {
+        MockResource {
+            data_source,
+            function: self.function,
+            method: self.method,
+            revision: self.revision,
+            dataset: self.dataset,
+            strict_parameters: self.strict_parameters,
+            parameters: self.parameters,
+            sequence: None,
+            comresult: self.comresult,
+            latency: self.latency,
+            faults: self.faults,
+            template: self.template,
+        }
+    }
+
+    /// Finishes the resource, responding with the contents of the given JSON file.
+    pub fn respond_with_file(self, file: &str) -> MockResource {
+        self.respond_with(FileOrString::File {
+            file: file.to_string(),
+        })
+    }
+
+    /// Finishes the resource, responding with the given JSON, e.g. `json!({ "ARTIKEL": { "ARTNR": "A1" } })`.
+    pub fn respond_with_json(self, body: serde_json::Value) -> MockResource {
+        self.respond_with(FileOrString::String {
+            value: body.to_string(),
+        })
+    }
+
+    /// Finishes the resource, responding with the serialized value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be serialized as a JSON object.
+    pub fn respond_with_serde<T: Serialize>(self, body: &T) -> MockResource {
+        let body = serde_json::to_value(body).expect("Failed to serialize the response body");
+        assert!(body.is_object(), "The response body has to be a JSON object");
+        self.respond_with_json(body)
+    }
+
+    /// Finishes the resource, responding with `count` generated records of the entity, see [FileOrString::Generated].
+    pub fn respond_with_generated(self, entity: GeneratedEntity, count: usize, seed: u64) -> MockResource {
+        self.respond_with(FileOrString::Generated { entity, count, seed })
+    }
+
+    /// Finishes the resource, responding without a body.
+    pub fn respond_empty(self) -> MockResource {
+        self.respond_with(FileOrString::Empty)
+    }
+
+    /// Finishes the resource, responding with the given data sources on successive calls, see [ResponseSequence].
+    pub fn respond_with_sequence(self, sequence: ResponseSequence) -> MockResource {
+        let mut resource = self.respond_empty();
+        resource.sequence = Some(sequence);
+        resource
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Serialize;
+    use serde_json::json;
+
+    use crate::{
+        routes::WebserviceRequest,
+        MockResourceMethod, MockResourceRevision, NumericRange,
+    };
+
+    use super::mock;
+
+    #[test]
+    fn build_resource() {
+        let resource = mock("ARTIKEL")
+            .method(MockResourceMethod::Get)
+            .revision(3)
+            .param("FELDER", "ART_1_25")
+            .param_regex("ARTNR", "^A")
+            .param_absent("MAXLINES")
+            .respond_with_file("data/artikel.json");
+        assert_eq!(resource.revision, MockResourceRevision::Exact(3));
+        assert_eq!(
+            resource.to_string(),
+            r#"MockResource { function: ARTIKEL, method: GET, revision: 3, parameters: {"ARTNR":"^A","FELDER":{"exact":"ART_1_25"},"MAXLINES":{"absent":true}} }"#
+        );
+
+        let resources = [resource];
+        let matches = |parameters: &[(&str, &str)]| {
+            WebserviceRequest::for_test("ARTIKEL.GET", 3, parameters).lookup_resource(&resources).is_ok()
+        };
+        assert_eq!(matches(&[("FELDER", "ART_1_25"), ("ARTNR", "A1")]), true);
+        assert_eq!(matches(&[("FELDER", "ART_1_2."), ("ARTNR", "A1")]), false);
+        assert_eq!(matches(&[("FELDER", "ART_1_25"), ("ARTNR", "B1")]), false);
+        assert_eq!(matches(&[("FELDER", "ART_1_25"), ("ARTNR", "A1"), ("MAXLINES", "10")]), false);
+    }
+
+    #[test]
+    fn any_params() {
+        let resources = [mock("ARTIKEL").respond_empty(), mock("KUNDE").param_any("KDNR").respond_empty()];
+        assert_eq!(WebserviceRequest::for_test("ARTIKEL.GET", 1, &[]).lookup_resource(&resources).is_ok(), true);
+        assert_eq!(WebserviceRequest::for_test("ARTIKEL.GET", 1, &[("ARTNR", "A1")]).lookup_resource(&resources).is_ok(), false);
+        assert_eq!(WebserviceRequest::for_test("KUNDE.GET", 9, &[("KDNR", "")]).lookup_resource(&resources).is_ok(), true);
+        assert_eq!(WebserviceRequest::for_test("KUNDE.GET", 9, &[]).lookup_resource(&resources).is_ok(), false);
+
+        let resources = [mock("ARTIKEL").any_params().respond_empty()];
+        assert_eq!(WebserviceRequest::for_test("ARTIKEL.GET", 1, &[("ARTNR", "A1")]).lookup_resource(&resources).is_ok(), true);
+    }
+
+    #[test]
+    fn strict_params() {
+        let resources = [mock("ARTIKEL")
+            .param_ignore_case("ARTNR", "a1")
+            .param_numeric("MAXLINES", NumericRange { lte: Some(100.0), ..Default::default() })
+            .param_not_regex("FELDER", "ART_1_25")
+            .strict_params()
+            .respond_empty()];
+        let matches = |parameters: &[(&str, &str)]| {
+            WebserviceRequest::for_test("ARTIKEL.GET", 1, parameters).lookup_resource(&resources).is_ok()
+        };
+        assert_eq!(matches(&[("ARTNR", "A1"), ("MAXLINES", "50"), ("FELDER", "SNR")]), true);
+        assert_eq!(matches(&[("ARTNR", "A1"), ("MAXLINES", "500"), ("FELDER", "SNR")]), false);
+        assert_eq!(matches(&[("ARTNR", "A1"), ("MAXLINES", "50"), ("FELDER", "ART_1_25")]), false);
+        assert_eq!(matches(&[("ARTNR", "A1"), ("MAXLINES", "50"), ("FELDER", "SNR"), ("SUCHBEGRIFF", "x")]), false);
+    }
+
+    #[test]
+    fn respond_with_serde() {
+        #[derive(Serialize)]
+        #[serde(rename_all = "UPPERCASE")]
+        struct Artikel {
+            artnr: String,
+        }
+
+        let resource = mock("ARTIKEL")
+            .method(MockResourceMethod::Insert)
+            .respond_with_serde(&json!({ "ARTIKEL": Artikel { artnr: "A1".to_string() } }));
+        assert_eq!(resource.data_source.read_json().unwrap(), Some(json!({ "ARTIKEL": { "ARTNR": "A1" } })));
+    }
+}
@@ -0,0 +1,230 @@
+use std::sync::Mutex;
+
+use axum::http::StatusCode;
+
+use crate::{
+    routes::{ComResult, ComResultBuilder, WebserviceRequest},
+    Cassette, CassetteInteraction,
+};
+
+/// Why a request didn't match the next interaction of the cassette.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Deviation {
+    /// The position of the expected interaction, starting at `1`.
+    position: usize,
+    /// The expected interaction, or `None` if the cassette has been played completely.
+    expected: Option<String>,
+    /// The request that was received instead.
+    received: String,
+}
+
+impl std::fmt::Display for Deviation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.expected {
+            Some(expected) => write!(
+                f,
+                "Interaction {}: expected {}, received {}",
+                self.position, expected, self.received
+            ),
+            None => write!(
+                f,
+                "Cassette finished after {} interactions, received {}",
+                self.position - 1,
+                self.received
+            ),
+        }
+    }
+}
+
+impl Deviation {
+    pub fn comresult(&self) -> ComResult {
+        ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
+            .bereich("WWSVC")
+            .code("400 Bad Request")
+            .info("Es wurde eine fehlerhafte Anforderung übergeben.")
+            .info2("wwsvc-mock: Request deviates from the cassette")
+            .info3(&self.to_string())
+            .build()
+            .unwrap()
+    }
+}
+
+fn matches(interaction: &CassetteInteraction, request: &WebserviceRequest) -> bool {
+    let Some((function, method)) = request.function_and_method() else {
+        return false;
+    };
+    let parameters = &request.function.parameter;
+
+    interaction.function == function
+        && interaction.method == method
+        && interaction.revision.matches(request.function.revision)
+        && parameters.len() == interaction.parameters.len()
+        && parameters.iter().all(|parameter| {
+            interaction
+                .parameters
+                .get(&parameter.name)
+                .is_some_and(|regex| regex.is_match(&parameter.value))
+        })
+}
+
+fn describe(request: &WebserviceRequest) -> String {
+    let parameters = request
+        .function
+        .parameter
+        .iter()
+        .map(|parameter| (&parameter.name, &parameter.value))
+        .collect::<std::collections::BTreeMap<_, _>>();
+    format!(
+        "{} (Revision {}) {}",
+        request.function.function_name,
+        request.function.revision,
+        serde_json::to_string(&parameters).unwrap()
+    )
+}
+
+/// Replays the interactions of a [Cassette] strictly in order.
+#[derive(Debug)]
+pub(crate) struct Replayer {
+    interactions: Vec<CassetteInteraction>,
+    position: Mutex<usize>,
+}
+
+impl Replayer {
+    pub fn new(cassette: &Cassette) -> anyhow::Result<Self> {
+        Ok(Replayer {
+            interactions: cassette.load_interactions()?,
+            position: Mutex::new(0),
+        })
+    }
+
+    /// Replays the next interaction for the request, or returns `None` if the function of the request doesn't appear in
+    /// the cassette at all.
+    pub fn replay(&self, request: &WebserviceRequest) -> Option<Result<CassetteInteraction, Deviation>> {
+        let (function, _) = request.function_and_method()?;
+        if !self
+            .interactions
+            .iter()
+            .any(|interaction| interaction.function == function)
+        {
+            return None;
+        }
+
+        let mut position = self.position.lock().unwrap();
+        let result = match self.interactions.get(*position) {
+            Some(interaction) if matches(interaction, request) => {
+                *position += 1;
+                Ok(interaction.clone())
+            }
+            expected => Err(Deviation {
+                position: *position + 1,
+                expected: expected.map(CassetteInteraction::to_string),
+                received: describe(request),
+            }),
+        };
+        Some(result)
+    }
+
+    /// The number of interactions that haven't been replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions.len() - *self.position.lock().unwrap()
+    }
+
+    /// Rewinds the cassette to the first interaction.
+    pub fn reset(&self) {
+        *self.position.lock().unwrap() = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{
+        providers::{Format, Toml},
+        Figment,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        routes::WebserviceRequest,
+        Cassette,
+    };
+
+    use super::Replayer;
+
+    fn request(function_name: &str, parameters: &[(&str, &str)]) -> WebserviceRequest {
+        WebserviceRequest::for_test(function_name, 1, parameters)
+    }
+
+    fn replayer() -> Replayer {
+        let cassette: Cassette = Figment::from(Toml::string(
+            r#"
+            [[interactions]]
+            function = "AUFTRAG"
+            method = "INSERT"
+            revision = 1
+            parameters = { KUNDE = "^10001$" }
+            response = { type = "String", value = '{"AUFTRAG": {"AUFNR": "1"}}' }
+
+            [[interactions]]
+            function = "AUFTRAG"
+            method = "PUT"
+            revision = "any"
+            parameters = { AUFNR = "^1$", BUCHEN = "^J$" }
+            "#,
+        ))
+        .extract()
+        .unwrap();
+        Replayer::new(&cassette).unwrap()
+    }
+
+    #[test]
+    fn replay_in_order() {
+        let replayer = replayer();
+        assert_eq!(replayer.replay(&request("ARTIKEL.GET", &[])).is_none(), true);
+
+        let interaction = replayer
+            .replay(&request("AUFTRAG.INSERT", &[("KUNDE", "10001")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(interaction.response.as_string(), r#"{"AUFTRAG": {"AUFNR": "1"}}"#);
+        assert_eq!(replayer.remaining(), 1);
+
+        replayer
+            .replay(&request("AUFTRAG.PUT", &[("BUCHEN", "J"), ("AUFNR", "1")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayer.remaining(), 0);
+
+        let deviation = replayer
+            .replay(&request("AUFTRAG.PUT", &[("AUFNR", "1")]))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(deviation.expected, None);
+        assert_eq!(deviation.position, 3);
+
+        replayer.reset();
+        assert_eq!(replayer.remaining(), 2);
+    }
+
+    #[test]
+    fn deviation() {
+        let replayer = replayer();
+        let deviation = replayer
+            .replay(&request("AUFTRAG.PUT", &[("AUFNR", "1"), ("BUCHEN", "J")]))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(deviation.position, 1);
+        assert_eq!(
+            deviation.expected.as_deref(),
+            Some(r#"AUFTRAG.INSERT (Revision 1) {"KUNDE":"^10001$"}"#)
+        );
+        assert_eq!(deviation.received, r#"AUFTRAG.PUT (Revision 1) {"AUFNR":"1","BUCHEN":"J"}"#);
+        assert_eq!(deviation.comresult().status, 400);
+
+        // extra parameters are a deviation as well
+        let deviation = replayer
+            .replay(&request("AUFTRAG.INSERT", &[("KUNDE", "10001"), ("EXTRA", "1")]))
+            .unwrap();
+        assert_eq!(deviation.is_err(), true);
+        assert_eq!(replayer.remaining(), 2);
+    }
+}
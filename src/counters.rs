@@ -0,0 +1,48 @@
+use std::{collections::HashMap, sync::Mutex};
+
+/// Counts how often something (e.g. a mock resource) has been called.
+#[derive(Debug, Default)]
+pub(crate) struct CallCounters {
+    counts: Mutex<HashMap<String, usize>>,
+}
+
+impl CallCounters {
+    /// Registers a call and returns how many calls happened before it, starting at `0`.
+    pub fn next(&self, key: &str) -> usize {
+        let mut counts = self.counts.lock().unwrap();
+        let count = counts.entry(key.to_string()).or_insert(0);
+        let call = *count;
+        *count += 1;
+        call
+    }
+
+    /// Forgets the calls with the given key.
+    pub fn forget(&self, key: &str) {
+        self.counts.lock().unwrap().remove(key);
+    }
+
+    /// Forgets all calls.
+    pub fn reset(&self) {
+        self.counts.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::CallCounters;
+
+    #[test]
+    fn counts_per_key() {
+        let counters = CallCounters::default();
+        assert_eq!(counters.next("a"), 0);
+        assert_eq!(counters.next("a"), 1);
+        assert_eq!(counters.next("b"), 0);
+        counters.forget("b");
+        assert_eq!(counters.next("b"), 0);
+        assert_eq!(counters.next("a"), 2);
+        counters.reset();
+        assert_eq!(counters.next("a"), 0);
+    }
+}
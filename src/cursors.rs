@@ -0,0 +1,154 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use axum::http::HeaderMap;
+
+use crate::{app_config::generate_hash, dataset::DatasetError, routes::WebserviceRequest};
+
+/// The header carrying the cursor, both in the request and the response.
+pub(crate) const CURSOR_HEADER: &str = "WWSVC-CURSOR";
+/// The header limiting the number of records per page of a cursor.
+pub(crate) const MAX_LINES_HEADER: &str = "WWSVC-ACCEPT-RESULT-MAX-LINES";
+/// The cursor a client sends to open a new cursor.
+const CREATE: &str = "CREATE";
+/// The cursor the server sends once there are no more records.
+const CLOSED: &str = "CLOSED";
+
+/// An open cursor: the request it was created for and how many records it has returned so far.
+#[derive(Debug)]
+struct OpenCursor {
+    query: String,
+    position: usize,
+}
+
+/// Returns the function, revision and parameters of a request in a form that doesn't depend on the parameter order.
+fn query(request: &WebserviceRequest) -> String {
+    let mut parameters = request
+        .function
+        .parameter
+        .iter()
+        .map(|parameter| format!("{}={}", parameter.name, parameter.value))
+        .collect::<Vec<_>>();
+    parameters.sort();
+    format!(
+        "{}@{}?{}",
+        request.function.function_name.to_uppercase(),
+        request.function.revision,
+        parameters.join("&")
+    )
+}
+
+/// Keeps track of the open pagination cursors and how many records they have returned so far.
+#[derive(Debug, Default)]
+pub(crate) struct CursorRegistry {
+    cursors: Mutex<HashMap<String, OpenCursor>>,
+}
+
+impl CursorRegistry {
+    /// Returns the cursor sent with the request, if any, along with its position. Fails if the cursor isn't open or
+    /// was created for another function or other parameters.
+    pub fn requested(
+        &self,
+        headers: &HeaderMap,
+        request: &WebserviceRequest,
+    ) -> Result<Option<(String, usize)>, DatasetError> {
+        let Some(cursor) = headers.get(CURSOR_HEADER).and_then(|cursor| cursor.to_str().ok()) else {
+            return Ok(None);
+        };
+        if cursor == CREATE {
+            return Ok(Some((cursor.to_string(), 0)));
+        }
+
+        match self.cursors.lock().unwrap().get(cursor) {
+            Some(open) if open.query == query(request) => Ok(Some((cursor.to_string(), open.position))),
+            Some(_) => Err(DatasetError::CursorMismatch(cursor.to_string())),
+            None => Err(DatasetError::UnknownCursor(cursor.to_string())),
+        }
+    }
+
+    /// Moves the cursor of the request to the given position and returns the cursor to send back, which is `CLOSED`
+    /// if there are no more records.
+    pub fn advance(&self, cursor: &str, request: &WebserviceRequest, position: usize, more: bool) -> String {
+        let mut cursors = self.cursors.lock().unwrap();
+        if !more {
+            cursors.remove(cursor);
+            return CLOSED.to_string();
+        }
+
+        let cursor = match cursor {
+            CREATE => generate_hash(),
+            cursor => cursor.to_string(),
+        };
+        cursors.insert(
+            cursor.clone(),
+            OpenCursor {
+                query: query(request),
+                position,
+            },
+        );
+        cursor
+    }
+
+    /// Closes all cursors.
+    pub fn reset(&self) {
+        self.cursors.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderMap;
+    use pretty_assertions::assert_eq;
+
+    use super::CursorRegistry;
+    use crate::{dataset::DatasetError, routes::WebserviceRequest};
+
+    fn headers(cursor: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("WWSVC-CURSOR", cursor.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn walk_cursor() {
+        let cursors = CursorRegistry::default();
+        let request = WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("ARTNR", "A*"), ("FELDER", "SNR")]);
+        assert_eq!(cursors.requested(&HeaderMap::new(), &request), Ok(None));
+        assert_eq!(cursors.requested(&headers("CREATE"), &request), Ok(Some(("CREATE".to_string(), 0))));
+
+        let cursor = cursors.advance("CREATE", &request, 10, true);
+        assert_eq!(cursors.requested(&headers(&cursor), &request), Ok(Some((cursor.clone(), 10))));
+        assert_eq!(cursors.advance(&cursor, &request, 20, true), cursor);
+        assert_eq!(cursors.requested(&headers(&cursor), &request), Ok(Some((cursor.clone(), 20))));
+
+        assert_eq!(cursors.advance(&cursor, &request, 25, false), "CLOSED");
+        assert_eq!(
+            cursors.requested(&headers(&cursor), &request),
+            Err(DatasetError::UnknownCursor(cursor.clone()))
+        );
+
+        let cursor = cursors.advance("CREATE", &request, 10, true);
+        cursors.reset();
+        assert_eq!(cursors.requested(&headers(&cursor), &request), Err(DatasetError::UnknownCursor(cursor)));
+    }
+
+    #[test]
+    fn cursor_belongs_to_request() {
+        let cursors = CursorRegistry::default();
+        let request = WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("ARTNR", "A*"), ("FELDER", "SNR")]);
+        let cursor = cursors.advance("CREATE", &request, 10, true);
+
+        let reordered = WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("FELDER", "SNR"), ("ARTNR", "A*")]);
+        assert_eq!(cursors.requested(&headers(&cursor), &reordered), Ok(Some((cursor.clone(), 10))));
+
+        for other in [
+            WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("ARTNR", "B*"), ("FELDER", "SNR")]),
+            WebserviceRequest::for_test("ARTIKEL.GET", 4, &[("ARTNR", "A*"), ("FELDER", "SNR")]),
+            WebserviceRequest::for_test("BELEG.GET", 3, &[("ARTNR", "A*"), ("FELDER", "SNR")]),
+        ] {
+            assert_eq!(
+                cursors.requested(&headers(&cursor), &other),
+                Err(DatasetError::CursorMismatch(cursor.clone()))
+            );
+        }
+    }
+}
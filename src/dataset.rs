@@ -0,0 +1,445 @@
+use std::cmp::Ordering;
+
+use axum::http::StatusCode;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    routes::{ComResult, ComResultBuilder, WebserviceRequest},
+    DatasetConfig, FieldFilter, FilterMode,
+};
+
+/// The parameter selecting the fields to return, e.g. `ART_1_25,ART_51_60`.
+pub(crate) const FIELDS_PARAMETER: &str = "FELDER";
+
+/// The reasons why a list response can't be processed for a request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatasetError {
+    /// The request asked for fields that no record has.
+    UnknownFields(Vec<String>),
+    /// The request sent a paging parameter that isn't a number.
+    InvalidParameter(String),
+    /// The request sent a cursor that isn't open.
+    UnknownCursor(String),
+    /// The request sent a cursor that was opened for another function or other parameters.
+    CursorMismatch(String),
+}
+
+impl DatasetError {
+    /// Returns the COMRESULT of the error, a `400 Bad Request` like the WEBWARE responds with to invalid list requests.
+    pub fn comresult(&self) -> ComResult {
+        let builder = ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
+            .bereich("WWSVC")
+            .code("400 Bad Request")
+            .info("Es wurde eine fehlerhafte Anforderung übergeben.");
+        match self {
+            DatasetError::UnknownFields(fields) => builder
+                .info2("Feld nicht bekannt.")
+                .info3(&fields.join(",")),
+            DatasetError::InvalidParameter(parameter) => builder
+                .info2("Parameter ungültig.")
+                .info3(parameter),
+            DatasetError::UnknownCursor(cursor) => builder
+                .info2("Cursor nicht bekannt.")
+                .info3(cursor),
+            DatasetError::CursorMismatch(cursor) => builder
+                .info2("Cursor gehört zu einer anderen Anfrage.")
+                .info3(cursor),
+        }
+        .build()
+        .unwrap()
+    }
+}
+
+/// Returns the record lists of a list response like `{"ARTIKELLISTE": {"ANZAHL": "1", "ARTIKEL": [...]}}`, along with
+/// the envelope containing them.
+fn lists_mut(body: &mut Value) -> Vec<&mut serde_json::Map<String, Value>> {
+    match body {
+        Value::Object(body) => body
+            .values_mut()
+            .filter_map(|envelope| envelope.as_object_mut())
+            .filter(|envelope| envelope.values().any(Value::is_array))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Applies `f` to every record list of the list response and updates its `ANZAHL`.
+fn for_each_list<F>(body: &mut Value, mut f: F) -> Result<(), DatasetError>
+where
+    F: FnMut(&mut Vec<Value>) -> Result<(), DatasetError>,
+{
+    for envelope in lists_mut(body) {
+        let mut count = 0;
+        for records in envelope.values_mut().filter_map(Value::as_array_mut) {
+            f(records)?;
+            count += records.len();
+        }
+        if envelope.contains_key("ANZAHL") {
+            envelope.insert("ANZAHL".to_string(), Value::String(count.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the requested fields that no record has. The fields have to be checked before the records are filtered, as
+/// the remaining records may just not have a value for them.
+fn unknown_fields(config: &DatasetConfig, body: &mut Value, fields: &[&str]) -> Vec<String> {
+    let mut unknown = vec![];
+    for_each_list(body, |records| {
+        unknown.extend(
+            fields
+                .iter()
+                .filter(|field| !config.system_fields.iter().any(|system| system == *field))
+                .filter(|field| {
+                    !records.is_empty()
+                        && !records.iter().any(|record| record.get(**field).is_some())
+                })
+                .filter(|field| !unknown.iter().any(|unknown| unknown == *field))
+                .map(|field| field.to_string())
+                .collect::<Vec<_>>(),
+        );
+        Ok(())
+    })
+    .unwrap();
+    unknown
+}
+
+/// Keeps only the requested fields of every record, along with the system fields.
+fn project(config: &DatasetConfig, body: &mut Value, fields: &[&str]) {
+    for_each_list(body, |records| {
+        for record in records.iter_mut().filter_map(Value::as_object_mut) {
+            record.retain(|field, _| {
+                fields.contains(&field.as_str()) || config.system_fields.contains(field)
+            });
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// A [FieldFilter] prepared for the value of a request, so a wildcard is compiled once instead of once per record.
+struct PreparedFilter<'a> {
+    filter: &'a FieldFilter,
+    value: String,
+    wildcard: Option<Regex>,
+}
+
+impl FieldFilter {
+    fn prepare(&self, value: &str) -> PreparedFilter<'_> {
+        let value = match self.case_insensitive {
+            true => value.to_lowercase(),
+            false => value.to_string(),
+        };
+        let wildcard = (self.mode == FilterMode::Wildcard).then(|| {
+            let pattern = value.split('*').map(regex::escape).collect::<Vec<_>>().join(".*");
+            Regex::new(&format!("^{}$", pattern)).expect("escaped wildcard pattern is a valid regex")
+        });
+        PreparedFilter {
+            filter: self,
+            value,
+            wildcard,
+        }
+    }
+}
+
+impl PreparedFilter<'_> {
+    /// Returns whether the field of a record matches the parameter value.
+    fn matches(&self, field: &str) -> bool {
+        let field = match self.filter.case_insensitive {
+            true => field.to_lowercase(),
+            false => field.to_string(),
+        };
+        match self.filter.mode {
+            FilterMode::Equals => field == self.value,
+            FilterMode::Prefix => field.starts_with(&self.value),
+            FilterMode::Wildcard => self.wildcard.as_ref().is_some_and(|wildcard| wildcard.is_match(&field)),
+            FilterMode::Min => compare_values(&field, &self.value).is_ge(),
+            FilterMode::Max => compare_values(&field, &self.value).is_le(),
+        }
+    }
+}
+
+/// Returns the sort key of a value: dates like `31.12.2024` as `20241231`, numbers like `1,5` as `1.5`.
+fn filter_key(value: &str) -> Option<f64> {
+    if let Some((day, month, year)) = parse_date(value) {
+        return Some((year * 10000 + month * 100 + day) as f64);
+    }
+    value.trim().replace(',', ".").parse::<f64>().ok()
+}
+
+/// Returns the value of a date like `31.12.2024`, if it is one.
+pub(crate) fn parse_date(value: &str) -> Option<(u32, u32, u32)> {
+    let parts = value.trim().split('.').collect::<Vec<_>>();
+    let [day, month, year] = parts[..] else {
+        return None;
+    };
+    let (day, month, year) = (day.parse::<u32>().ok()?, month.parse::<u32>().ok()?, year.parse::<u32>().ok()?);
+    ((1..=31).contains(&day) && (1..=12).contains(&month) && year.to_string().len() == 4).then_some((day, month, year))
+}
+
+/// Compares two values as dates or numbers if both are, alphabetically otherwise.
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (filter_key(a), filter_key(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+/// Keeps only the records matching the filters of the sent parameters. Empty parameters don't filter.
+fn filter(config: &DatasetConfig, request: &WebserviceRequest, body: &mut Value) -> Result<(), DatasetError> {
+    let filters = config
+        .filters
+        .iter()
+        .filter_map(|(parameter, filter)| {
+            request
+                .parameter(parameter)
+                .filter(|value| !value.is_empty())
+                .map(|value| filter.prepare(value))
+        })
+        .collect::<Vec<_>>();
+    if filters.is_empty() {
+        return Ok(());
+    }
+
+    for_each_list(body, |records| {
+        records.retain(|record| {
+            filters.iter().all(|filter| {
+                match record.get(&filter.filter.field) {
+                    Some(Value::String(field)) => filter.matches(field),
+                    Some(Value::Number(field)) => filter.matches(&field.to_string()),
+                    _ => false,
+                }
+            })
+        });
+        Ok(())
+    })
+}
+
+/// Keeps at most `max_lines` records of every list, starting after the first `skip` records. Returns whether any list
+/// has more records after the page.
+pub(crate) fn paginate(body: &mut Value, skip: usize, max_lines: Option<usize>) -> bool {
+    let mut more = false;
+    for_each_list(body, |records| {
+        let end = max_lines.map_or(records.len(), |max_lines| skip.saturating_add(max_lines));
+        more |= end < records.len();
+        records.truncate(end);
+        records.drain(..skip.min(records.len()));
+        Ok(())
+    })
+    .unwrap();
+    more
+}
+
+/// Parses a paging parameter, if it was sent and isn't empty.
+fn paging_parameter(request: &WebserviceRequest, name: &str) -> Result<Option<usize>, DatasetError> {
+    match request.parameter(name).map(str::trim) {
+        Some("") | None => Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| DatasetError::InvalidParameter(name.to_string())),
+    }
+}
+
+/// Processes a list response for a GET request like the WEBWARE would, e.g. keeping only the records matching the
+/// [filters][DatasetConfig::filters] and the requested `FELDER`, paged by the paging parameters.
+pub(crate) fn apply(config: &DatasetConfig, request: &WebserviceRequest, body: Value) -> Result<Value, DatasetError> {
+    let mut body = select(config, request, body)?;
+
+    let max_lines = paging_parameter(request, &config.max_lines_parameter)?;
+    let start = paging_parameter(request, &config.start_parameter)?;
+    if max_lines.is_some() || start.is_some() {
+        paginate(&mut body, start.unwrap_or(1).saturating_sub(1), max_lines);
+    }
+
+    Ok(body)
+}
+
+/// Like [apply], but without paging, for requests paged with a cursor.
+pub(crate) fn select(
+    config: &DatasetConfig,
+    request: &WebserviceRequest,
+    mut body: Value,
+) -> Result<Value, DatasetError> {
+    let fields = request
+        .parameter(FIELDS_PARAMETER)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .collect::<Vec<_>>();
+    if !fields.is_empty() {
+        let unknown = unknown_fields(config, &mut body, &fields);
+        if !unknown.is_empty() {
+            return Err(DatasetError::UnknownFields(unknown));
+        }
+    }
+
+    filter(config, request, &mut body)?;
+
+    if !fields.is_empty() {
+        project(config, &mut body, &fields);
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+
+    use crate::{
+        routes::WebserviceRequest,
+        DatasetConfig, FieldFilter, FilterMode,
+    };
+
+    use super::DatasetError;
+
+    fn request(parameters: &[(&str, &str)]) -> WebserviceRequest {
+        WebserviceRequest::for_test("ARTIKEL.GET", 3, parameters)
+    }
+
+    fn dataset() -> Value {
+        json!({
+            "ARTIKELLISTE": {
+                "ANZAHL": "2",
+                "ARTIKEL": [
+                    { "ART_1_25": "A1", "ART_51_60": "Apfel", "ART_PREIS": "1,50", "SNR": "1" },
+                    { "ART_1_25": "B1", "ART_51_60": "Birne", "SNR": "2" }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn project_fields() {
+        let body = super::apply(
+            &DatasetConfig::default(),
+            &request(&[("FELDER", "ART_1_25, ART_PREIS")]),
+            dataset(),
+        )
+        .unwrap();
+        assert_eq!(body, json!({
+            "ARTIKELLISTE": {
+                "ANZAHL": "2",
+                "ARTIKEL": [
+                    { "ART_1_25": "A1", "ART_PREIS": "1,50", "SNR": "1" },
+                    { "ART_1_25": "B1", "SNR": "2" }
+                ]
+            }
+        }));
+
+        let body = super::apply(&DatasetConfig::default(), &request(&[]), dataset()).unwrap();
+        assert_eq!(body, dataset());
+    }
+
+    #[test]
+    fn filter_records() {
+        let config = DatasetConfig::default()
+            .with_filter("ARTNR", FieldFilter::new("ART_1_25", FilterMode::Wildcard).ignore_case())
+            .with_filter("SUCHBEGRIFF", FieldFilter::new("ART_51_60", FilterMode::Prefix))
+            .with_filter("PREIS_VON", FieldFilter::new("ART_PREIS", FilterMode::Min));
+
+        let body = super::apply(&config, &request(&[("ARTNR", "b*"), ("FELDER", "ART_1_25")]), dataset()).unwrap();
+        assert_eq!(body, json!({
+            "ARTIKELLISTE": {
+                "ANZAHL": "1",
+                "ARTIKEL": [{ "ART_1_25": "B1", "SNR": "2" }]
+            }
+        }));
+
+        let body = super::apply(&config, &request(&[("SUCHBEGRIFF", "Apf"), ("ARTNR", "")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "1");
+        assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0]["SNR"], "1");
+
+        let body = super::apply(&config, &request(&[("PREIS_VON", "1,6")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"], json!({ "ANZAHL": "0", "ARTIKEL": [] }));
+    }
+
+    #[test]
+    fn fields_are_checked_before_filtering() {
+        let config = DatasetConfig::default()
+            .with_filter("ARTNR", FieldFilter::new("ART_1_25", FilterMode::Wildcard));
+
+        // B1 has no price, but other articles do
+        let body = super::apply(&config, &request(&[("ARTNR", "B*"), ("FELDER", "ART_PREIS")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"], json!({ "ANZAHL": "1", "ARTIKEL": [{ "SNR": "2" }] }));
+
+        let body = super::apply(&config, &request(&[("ARTNR", "X*"), ("FELDER", "ART_1_25")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"], json!({ "ANZAHL": "0", "ARTIKEL": [] }));
+
+        let err = super::apply(&config, &request(&[("ARTNR", "X*"), ("FELDER", "ART_UNBEKANNT")]), dataset()).unwrap_err();
+        assert_eq!(err, DatasetError::UnknownFields(vec!["ART_UNBEKANNT".to_string()]));
+    }
+
+    #[test]
+    fn filter_modes() {
+        let min = FieldFilter::new("DATUM", FilterMode::Min);
+        assert!(min.prepare("01.02.2024").matches("31.12.2024"));
+        assert!(!min.prepare("01.02.2025").matches("31.12.2024"));
+        assert!(min.prepare("9").matches("10"));
+        assert!(!min.prepare("B").matches("A"));
+
+        let max = FieldFilter::new("MENGE", FilterMode::Max);
+        assert!(max.prepare("1,5").matches("1.5"));
+        assert!(!max.prepare("1,5").matches("2"));
+
+        let equals = FieldFilter::new("ART_1_25", FilterMode::Equals);
+        assert!(equals.prepare("A1").matches("A1"));
+        assert!(!equals.prepare("a1").matches("A1"));
+        assert!(equals.ignore_case().prepare("a1").matches("A1"));
+
+        let wildcard = FieldFilter::new("ART_1_25", FilterMode::Wildcard);
+        assert!(wildcard.prepare("ART*.0001?").matches("ART00001.0001?"));
+        assert!(!wildcard.prepare("ART*.0001?").matches("ART00001.00010"));
+        assert!(wildcard.ignore_case().prepare("art*").matches("ART00001"));
+    }
+
+    #[test]
+    fn paging_parameters() {
+        let config = DatasetConfig::default();
+        let body = super::apply(&config, &request(&[("MAXLINES", "1"), ("STARTPOS", "2")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"], json!({
+            "ANZAHL": "1",
+            "ARTIKEL": [{ "ART_1_25": "B1", "ART_51_60": "Birne", "SNR": "2" }]
+        }));
+
+        let body = super::apply(&config, &request(&[("MAXLINES", "1")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0]["SNR"], "1");
+
+        let body = super::apply(&config, &request(&[("STARTPOS", "3")]), dataset()).unwrap();
+        assert_eq!(body["ARTIKELLISTE"], json!({ "ANZAHL": "0", "ARTIKEL": [] }));
+
+        let err = super::apply(&config, &request(&[("MAXLINES", "viele")]), dataset()).unwrap_err();
+        assert_eq!(err, DatasetError::InvalidParameter("MAXLINES".to_string()));
+    }
+
+    #[test]
+    fn paginate() {
+        let mut body = dataset();
+        assert_eq!(super::paginate(&mut body, 0, Some(1)), true);
+        assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "1");
+
+        let mut body = dataset();
+        assert_eq!(super::paginate(&mut body, 1, Some(1)), false);
+        assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0]["SNR"], "2");
+
+        let mut body = dataset();
+        assert_eq!(super::paginate(&mut body, 0, None), false);
+        assert_eq!(body, dataset());
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let err = super::apply(
+            &DatasetConfig::default(),
+            &request(&[("FELDER", "ART_1_25,ART_UNBEKANNT,SNR")]),
+            dataset(),
+        )
+        .unwrap_err();
+        assert_eq!(err, DatasetError::UnknownFields(vec!["ART_UNBEKANNT".to_string()]));
+        assert_eq!(err.comresult().info3.as_deref(), Some("ART_UNBEKANNT"));
+    }
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+
+use crate::{routes::WebserviceRequest, MockResource, ParameterMatcher};
+
+/// The number of near misses reported for an unmatched request.
+const MAX_NEAR_MISSES: usize = 3;
+
+/// A mock resource that almost matched a request, along with the reasons why it didn't.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    /// The mock resource, formatted like in the logs.
+    pub resource: String,
+    /// The criteria that didn't match, e.g. `revision 3 doesn't match 2`.
+    pub mismatches: Vec<String>,
+}
+
+fn mismatches(request: &WebserviceRequest, resource: &MockResource) -> Vec<String> {
+    let mut mismatches = vec![];
+
+    let (function, method) = request
+        .function
+        .function_name
+        .split_once('.')
+        .unwrap_or((&request.function.function_name, ""));
+    if resource.function != function {
+        mismatches.push(format!("function {} doesn't match {}", function, resource.function));
+    }
+    if !resource.method.to_string().eq_ignore_ascii_case(method) {
+        mismatches.push(format!("method {} doesn't match {}", method, resource.method));
+    }
+    if !resource.revision.matches(request.function.revision) {
+        mismatches.push(format!(
+            "revision {} doesn't match {}",
+            request.function.revision, resource.revision
+        ));
+    }
+
+    match &resource.parameters {
+        Some(parameters) => {
+            let mut parameters = parameters.iter().collect::<Vec<_>>();
+            parameters.sort_by_key(|(name, _)| name.as_str());
+            for (name, matcher) in parameters {
+                let values = request.parameter_values(name);
+                match (values.as_slice(), matcher) {
+                    (_, matcher) if matcher.matches(&values) => {}
+                    ([], _) => mismatches.push(format!("parameter {} is missing", name)),
+                    (values, ParameterMatcher::Absent) => mismatches.push(format!(
+                        "parameter {}={:?} should be absent",
+                        name,
+                        values.join(",")
+                    )),
+                    (values, matcher) => mismatches.push(format!(
+                        "parameter {}={:?} doesn't match {}",
+                        name,
+                        values.join(","),
+                        matcher
+                    )),
+                }
+            }
+
+            if resource.strict_parameters {
+                let mut unexpected = request
+                    .function
+                    .parameter
+                    .iter()
+                    .map(|parameter| parameter.name.as_str())
+                    .filter(|name| !resource.parameters.as_ref().is_some_and(|parameters| parameters.contains_key(*name)))
+                    .filter(|name| !resource.handles_parameter(name))
+                    .collect::<Vec<_>>();
+                unexpected.sort();
+                unexpected.dedup();
+                for name in unexpected {
+                    mismatches.push(format!("parameter {} isn't expected", name));
+                }
+            }
+        }
+        None if !request
+            .function
+            .parameter
+            .iter()
+            .all(|parameter| resource.handles_parameter(&parameter.name)) =>
+        {
+            mismatches.push("resource expects no parameters".to_string())
+        }
+        None => {}
+    }
+
+    mismatches
+}
+
+/// Finds the mock resources that came closest to matching the request.
+///
+/// Resources for the same function are preferred, then the ones with the fewest mismatching criteria.
+pub(crate) fn near_misses(request: &WebserviceRequest, resources: &[MockResource]) -> Vec<NearMiss> {
+    let function = request
+        .function
+        .function_name
+        .split_once('.')
+        .map_or(request.function.function_name.as_str(), |(function, _)| function);
+
+    let mut near_misses = resources
+        .iter()
+        .map(|resource| {
+            (
+                resource.function != function,
+                NearMiss {
+                    resource: resource.to_string(),
+                    mismatches: mismatches(request, resource),
+                },
+            )
+        })
+        .filter(|(_, near_miss)| !near_miss.mismatches.is_empty())
+        .collect::<Vec<_>>();
+    // stable sort, so resources that were configured first win ties
+    near_misses.sort_by_key(|(other_function, near_miss)| (*other_function, near_miss.mismatches.len()));
+
+    near_misses
+        .into_iter()
+        .take(MAX_NEAR_MISSES)
+        .map(|(_, near_miss)| near_miss)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        routes::WebserviceRequest,
+        FileOrString, MockResource, MockResourceMethod, ParameterMatcher,
+    };
+
+    fn resource(function: &str, revision: u32, parameters: Option<&[(&str, &str)]>) -> MockResource {
+        MockResource {
+            data_source: FileOrString::Empty,
+            function: function.to_string(),
+            method: MockResourceMethod::Get,
+            revision: revision.into(),
+            parameters: parameters.map(|parameters| {
+                parameters
+                    .iter()
+                    .map(|(name, regex)| (name.to_string(), ParameterMatcher::regex(regex).unwrap()))
+                    .collect()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn near_misses_explain_mismatches() {
+        let resources = vec![
+            resource("KUNDE", 3, None),
+            resource("ARTIKEL", 2, Some(&[("FELDER", "ART_1_25")])),
+            resource("ARTIKEL", 3, Some(&[("FELDER", "ART_1_25"), ("ARTNR", "^A")])),
+        ];
+
+        let near_misses = super::near_misses(
+            &WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("FELDER", "ART_1_25"), ("ARTNR", "B1")]),
+            &resources,
+        );
+        assert_eq!(near_misses.len(), 3);
+        assert_eq!(near_misses[0].mismatches, vec!["revision 3 doesn't match 2"]);
+        assert_eq!(near_misses[1].mismatches, vec![r#"parameter ARTNR="B1" doesn't match /^A/"#]);
+        assert_eq!(
+            near_misses[2].mismatches,
+            vec!["function ARTIKEL doesn't match KUNDE", "resource expects no parameters"]
+        );
+
+        let near_misses = super::near_misses(&WebserviceRequest::for_test("ARTIKEL.PUT", 3, &[]), &resources[2..]);
+        assert_eq!(
+            near_misses[0].mismatches,
+            vec![
+                "method PUT doesn't match GET",
+                "parameter ARTNR is missing",
+                "parameter FELDER is missing"
+            ]
+        );
+
+        let mut absent = resource("ARTIKEL", 3, Some(&[]));
+        absent.parameters.as_mut().unwrap().insert("ARTNR".to_string(), ParameterMatcher::Absent);
+        absent.parameters.as_mut().unwrap().insert("FELDER".to_string(), ParameterMatcher::exact("ART_1_25"));
+        let near_misses = super::near_misses(&WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("ARTNR", "A1"), ("FELDER", "SNR")]), &[absent]);
+        assert_eq!(
+            near_misses[0].mismatches,
+            vec![r#"parameter ARTNR="A1" should be absent"#, r#"parameter FELDER="SNR" doesn't match "ART_1_25""#]
+        );
+
+        let mut strict = resource("ARTIKEL", 3, Some(&[("FELDER", "ART_1_25")]));
+        strict.strict_parameters = true;
+        let near_misses = super::near_misses(&WebserviceRequest::for_test("ARTIKEL.GET", 3, &[("FELDER", "ART_1_25"), ("ARTNR", "A1")]), &[strict]);
+        assert_eq!(near_misses[0].mismatches, vec!["parameter ARTNR isn't expected"]);
+    }
+}
@@ -0,0 +1,398 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use axum::http::StatusCode;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    routes::{ComResult, ComResultBuilder, WebserviceParameter},
+    MockResourceMethod, StatefulFunction,
+};
+
+type Record = Map<String, Value>;
+
+/// The reasons why a call to a stateful function can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreError {
+    /// The request didn't contain the key parameter.
+    MissingKey(String),
+    /// A record with the given key already exists.
+    AlreadyExists(String),
+    /// No record with the given key exists.
+    NotFound(String),
+    /// The method isn't supported by stateful functions.
+    UnsupportedMethod,
+}
+
+impl StoreError {
+    /// Returns the COMRESULT of the error. Errors about records are reported as function errors (`ERRNO` 1), an
+    /// unsupported method as an unknown function, like WEBWARE does for e.g. `ARTIKEL.EXEC`.
+    pub fn comresult(&self) -> ComResult {
+        let builder = ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
+            .bereich("WWSVC")
+            .code("400 Bad Request")
+            .info("Es wurde eine fehlerhafte Anforderung übergeben.");
+        let function_error = |builder: ComResultBuilder| builder.errno("1").errnotxt("SVCERR_FUNCTION_ERROR (1)");
+        match self {
+            StoreError::MissingKey(parameter) => function_error(builder)
+                .info2("Pflichtparameter fehlt.")
+                .info3(parameter),
+            StoreError::AlreadyExists(key) => function_error(builder)
+                .info2("Datensatz existiert bereits.")
+                .info3(key),
+            StoreError::NotFound(key) => function_error(builder)
+                .info2("Datensatz nicht gefunden.")
+                .info3(key),
+            StoreError::UnsupportedMethod => builder
+                .info2("Methode wird nicht unterstützt.")
+                .errno("20")
+                .errnotxt("SVCERR_UNKNOWN_FUNCTION (20)"),
+        }
+        .build()
+        .unwrap()
+    }
+}
+
+#[derive(Debug)]
+struct Table {
+    function: StatefulFunction,
+    records: Vec<Record>,
+}
+
+impl Table {
+    fn seed(function: &StatefulFunction) -> anyhow::Result<Self> {
+        let records = match function.seed.read_json()? {
+            None => vec![],
+            Some(Value::Array(records)) => records,
+            Some(Value::Object(mut envelope)) => match envelope
+                .remove(&function.list_name())
+                .and_then(|mut list| list.get_mut(function.item_name()).map(Value::take))
+            {
+                Some(Value::Array(records)) => records,
+                _ => anyhow::bail!(
+                    "Seed of stateful function {} has no {}.{} list",
+                    function.function,
+                    function.list_name(),
+                    function.item_name()
+                ),
+            },
+            Some(_) => anyhow::bail!(
+                "Seed of stateful function {} is neither a list response nor an array",
+                function.function
+            ),
+        };
+
+        let records = records
+            .into_iter()
+            .map(|record| match record {
+                Value::Object(record) => Ok(record),
+                _ => Err(anyhow::anyhow!(
+                    "Seed of stateful function {} contains a record that isn't an object",
+                    function.function
+                )),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Table {
+            function: function.clone(),
+            records,
+        })
+    }
+
+    fn key_of<'a>(&self, parameters: &'a [WebserviceParameter]) -> Option<&'a str> {
+        parameters
+            .iter()
+            .find(|parameter| parameter.name == self.function.key_parameter())
+            .map(|parameter| parameter.value.as_str())
+    }
+
+    fn require_key<'a>(&self, parameters: &'a [WebserviceParameter]) -> Result<&'a str, StoreError> {
+        self.key_of(parameters)
+            .ok_or_else(|| StoreError::MissingKey(self.function.key_parameter().to_string()))
+    }
+
+    /// Finds the record with the given key. Parameters are always strings, so numeric keys in the seed (e.g. `"SNR": 304`)
+    /// are compared by their string representation.
+    fn position(&self, key: &str) -> Option<usize> {
+        self.records.iter().position(|record| match record.get(&self.function.key_field) {
+            Some(Value::String(value)) => value == key,
+            Some(Value::Number(value)) => value.to_string() == key,
+            _ => false,
+        })
+    }
+
+    /// Copies the request parameters into the record, storing the key parameter under the key field. An existing key is
+    /// kept as it is, so numeric keys stay numbers.
+    fn apply(&self, record: &mut Record, parameters: &[WebserviceParameter]) {
+        for parameter in parameters {
+            if parameter.name == self.function.key_parameter() {
+                record
+                    .entry(self.function.key_field.clone())
+                    .or_insert_with(|| Value::String(parameter.value.clone()));
+            } else {
+                record.insert(parameter.name.clone(), Value::String(parameter.value.clone()));
+            }
+        }
+    }
+
+    fn next_snr(&self) -> u64 {
+        self.records
+            .iter()
+            .filter_map(|record| record.get("SNR"))
+            .filter_map(|snr| match snr {
+                Value::String(snr) => snr.parse::<u64>().ok(),
+                Value::Number(snr) => snr.as_u64(),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    fn list(&self, records: Vec<Record>) -> Value {
+        json!({
+            self.function.list_name(): {
+                "ANZAHL": records.len().to_string(),
+                self.function.item_name(): records,
+            }
+        })
+    }
+
+    fn item(&self, record: Record) -> Value {
+        json!({ self.function.item_name(): record })
+    }
+
+    fn execute(
+        &mut self,
+        method: &MockResourceMethod,
+        parameters: &[WebserviceParameter],
+    ) -> Result<Option<Value>, StoreError> {
+        match method {
+            MockResourceMethod::Get => {
+                let records = match self.key_of(parameters) {
+                    Some(key) => self.position(key).map(|i| self.records[i].clone()).into_iter().collect(),
+                    None => self.records.clone(),
+                };
+                Ok(Some(self.list(records)))
+            }
+            MockResourceMethod::Insert => {
+                let key = self.require_key(parameters)?;
+                if self.position(key).is_some() {
+                    return Err(StoreError::AlreadyExists(key.to_string()));
+                }
+
+                let mut record = Record::new();
+                self.apply(&mut record, parameters);
+                record
+                    .entry("SNR")
+                    .or_insert_with(|| Value::String(self.next_snr().to_string()));
+                self.records.push(record.clone());
+                Ok(Some(self.item(record)))
+            }
+            MockResourceMethod::Put => {
+                let key = self.require_key(parameters)?;
+                let i = self
+                    .position(key)
+                    .ok_or_else(|| StoreError::NotFound(key.to_string()))?;
+
+                let mut record = self.records[i].clone();
+                self.apply(&mut record, parameters);
+                self.records[i] = record.clone();
+                Ok(Some(self.item(record)))
+            }
+            MockResourceMethod::Delete => {
+                let key = self.require_key(parameters)?;
+                let i = self
+                    .position(key)
+                    .ok_or_else(|| StoreError::NotFound(key.to_string()))?;
+
+                self.records.remove(i);
+                Ok(None)
+            }
+            MockResourceMethod::Exec => Err(StoreError::UnsupportedMethod),
+        }
+    }
+}
+
+/// Holds the in-memory tables of all [stateful functions][StatefulFunction].
+#[derive(Debug)]
+pub(crate) struct EntityStore {
+    functions: Vec<StatefulFunction>,
+    tables: Mutex<HashMap<String, Table>>,
+}
+
+impl EntityStore {
+    pub fn new(functions: &[StatefulFunction]) -> anyhow::Result<Self> {
+        let store = EntityStore {
+            functions: functions.to_vec(),
+            tables: Mutex::new(HashMap::new()),
+        };
+        store.reset()?;
+        Ok(store)
+    }
+
+    /// Reseeds all tables, discarding every change made since.
+    pub fn reset(&self) -> anyhow::Result<()> {
+        let tables = self
+            .functions
+            .iter()
+            .map(|function| Ok((function.function.clone(), Table::seed(function)?)))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+        *self.tables.lock().unwrap() = tables;
+        Ok(())
+    }
+
+    /// Executes a call to a stateful function, returning `None` if the function isn't stateful.
+    pub fn execute(
+        &self,
+        function: &str,
+        method: &MockResourceMethod,
+        parameters: &[WebserviceParameter],
+    ) -> Option<Result<Option<Value>, StoreError>> {
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.get_mut(function)?;
+        Some(table.execute(method, parameters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::{routes::WebserviceParameter, FileOrString, MockResourceMethod, StatefulFunction};
+
+    use super::{EntityStore, StoreError};
+
+    fn store() -> EntityStore {
+        EntityStore::new(&[StatefulFunction {
+            function: "ARTIKEL".to_string(),
+            key_field: "ART_1_25".to_string(),
+            key_parameter: Some("ARTNR".to_string()),
+            list_name: None,
+            item_name: None,
+            seed: FileOrString::String {
+                value: json!({
+                    "ARTIKELLISTE": {
+                        "ANZAHL": "1",
+                        "ARTIKEL": [{ "ART_1_25": "A1", "SNR": "304" }]
+                    }
+                })
+                .to_string(),
+            },
+        }])
+        .unwrap()
+    }
+
+    fn parameters(parameters: &[(&str, &str)]) -> Vec<WebserviceParameter> {
+        parameters
+            .iter()
+            .map(|(name, value)| WebserviceParameter {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn get_returns_seed() {
+        let store = store();
+        let result = store.execute("ARTIKEL", &MockResourceMethod::Get, &[]).unwrap().unwrap();
+        assert_eq!(result, Some(json!({
+            "ARTIKELLISTE": {
+                "ANZAHL": "1",
+                "ARTIKEL": [{ "ART_1_25": "A1", "SNR": "304" }]
+            }
+        })));
+        assert_eq!(store.execute("KUNDE", &MockResourceMethod::Get, &[]).is_none(), true);
+    }
+
+    #[test]
+    fn insert_put_delete() {
+        let store = store();
+        let inserted = store
+            .execute("ARTIKEL", &MockResourceMethod::Insert, &parameters(&[("ARTNR", "A2"), ("ART_51_60", "Neu")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(inserted, Some(json!({ "ARTIKEL": { "ART_1_25": "A2", "ART_51_60": "Neu", "SNR": "305" } })));
+
+        store
+            .execute("ARTIKEL", &MockResourceMethod::Put, &parameters(&[("ARTNR", "A2"), ("ART_51_60", "Geändert")]))
+            .unwrap()
+            .unwrap();
+        let result = store
+            .execute("ARTIKEL", &MockResourceMethod::Get, &parameters(&[("ARTNR", "A2")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(result, Some(json!({
+            "ARTIKELLISTE": {
+                "ANZAHL": "1",
+                "ARTIKEL": [{ "ART_1_25": "A2", "ART_51_60": "Geändert", "SNR": "305" }]
+            }
+        })));
+
+        store
+            .execute("ARTIKEL", &MockResourceMethod::Delete, &parameters(&[("ARTNR", "A1")]))
+            .unwrap()
+            .unwrap();
+        let result = store.execute("ARTIKEL", &MockResourceMethod::Get, &[]).unwrap().unwrap().unwrap();
+        assert_eq!(result["ARTIKELLISTE"]["ANZAHL"], "1");
+
+        store.reset().unwrap();
+        let result = store.execute("ARTIKEL", &MockResourceMethod::Get, &[]).unwrap().unwrap().unwrap();
+        assert_eq!(result["ARTIKELLISTE"]["ARTIKEL"][0]["ART_1_25"], "A1");
+    }
+
+    #[test]
+    fn errors() {
+        let store = store();
+        assert_eq!(
+            store.execute("ARTIKEL", &MockResourceMethod::Insert, &parameters(&[("ARTNR", "A1")])).unwrap(),
+            Err(StoreError::AlreadyExists("A1".to_string()))
+        );
+        assert_eq!(
+            store.execute("ARTIKEL", &MockResourceMethod::Put, &parameters(&[("ARTNR", "A2")])).unwrap(),
+            Err(StoreError::NotFound("A2".to_string()))
+        );
+        assert_eq!(
+            store.execute("ARTIKEL", &MockResourceMethod::Delete, &[]).unwrap(),
+            Err(StoreError::MissingKey("ARTNR".to_string()))
+        );
+        assert_eq!(
+            store.execute("ARTIKEL", &MockResourceMethod::Exec, &[]).unwrap(),
+            Err(StoreError::UnsupportedMethod)
+        );
+
+        let comresult = StoreError::NotFound("A2".to_string()).comresult();
+        assert_eq!(comresult.errno.as_deref(), Some("1"));
+        assert_eq!(comresult.errnotxt.as_deref(), Some("SVCERR_FUNCTION_ERROR (1)"));
+    }
+
+    #[test]
+    fn numeric_keys() {
+        let store = EntityStore::new(&[StatefulFunction {
+            function: "KUNDE".to_string(),
+            key_field: "SNR".to_string(),
+            key_parameter: None,
+            list_name: None,
+            item_name: None,
+            seed: FileOrString::String {
+                value: json!([{ "SNR": 304, "KUN_NAME": "Alt" }]).to_string(),
+            },
+        }])
+        .unwrap();
+
+        let updated = store
+            .execute("KUNDE", &MockResourceMethod::Put, &parameters(&[("SNR", "304"), ("KUN_NAME", "Neu")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated, Some(json!({ "KUNDE": { "SNR": 304, "KUN_NAME": "Neu" } })));
+        assert_eq!(
+            store.execute("KUNDE", &MockResourceMethod::Insert, &parameters(&[("SNR", "304")])).unwrap(),
+            Err(StoreError::AlreadyExists("304".to_string()))
+        );
+        store
+            .execute("KUNDE", &MockResourceMethod::Delete, &parameters(&[("SNR", "304")]))
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::Mutex;
+
+use axum::{
+    body::{Body, Bytes},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::{
+    routes::{ComResultBuilder, ServiceResponse},
+    FaultConfig, FaultKind,
+};
+
+const ERROR_PAGE: &str = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd">
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head>
+<meta http-equiv="Content-Type" content="text/html; charset=iso-8859-1"/>
+<title>500 - Internal server error.</title>
+</head>
+<body>
+<div id="header"><h1>Server Error</h1></div>
+<div id="content">
+ <div class="content-container"><fieldset>
+  <h2>500 - Internal server error.</h2>
+  <h3>There is a problem with the resource you are looking for, and it cannot be displayed.</h3>
+ </fieldset></div>
+</div>
+</body>
+</html>
+"#;
+
+/// Decides whether to inject a fault into a response, using a seedable random number generator.
+#[derive(Debug)]
+pub(crate) struct FaultInjector {
+    seed: u64,
+    rng: Mutex<StdRng>,
+}
+
+impl FaultInjector {
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(rand::random);
+        FaultInjector {
+            seed,
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    /// The seed of the random number generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Reseeds the random number generator, so the faults repeat from the start.
+    pub fn reset(&self) {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Randomly picks a fault to inject, or `None` if the response should be left alone.
+    pub fn pick(&self, config: Option<&FaultConfig>) -> Option<FaultKind> {
+        let config = config?;
+        if config.kinds.is_empty() {
+            return None;
+        }
+
+        let mut rng = self.rng.lock().unwrap();
+        if !rng.gen_bool(config.probability.clamp(0.0, 1.0)) {
+            return None;
+        }
+        Some(config.kinds[rng.gen_range(0..config.kinds.len())])
+    }
+}
+
+/// Marks a response whose connection is closed before anything is sent.
+#[derive(Debug, Clone, Copy)]
+struct DroppedConnection;
+
+/// Returns the HTTP status code the client receives, or `0` if the connection is closed before the response is sent.
+pub(crate) fn sent_status(response: &Response) -> u16 {
+    match response.extensions().get::<DroppedConnection>() {
+        Some(_) => 0,
+        None => response.status().as_u16(),
+    }
+}
+
+impl FaultKind {
+    /// Turns the regular response into a faulty one.
+    pub fn respond<T: Serialize>(&self, response: ServiceResponse<T>) -> Response {
+        match self {
+            FaultKind::InternalServerError => ServiceResponse {
+                comresult: ComResultBuilder::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .bereich("WWSVC")
+                    .code("500 Internal Server Error")
+                    .info("Es ist ein interner Fehler aufgetreten.")
+                    .info2("wwsvc-mock: Injected fault")
+                    .build()
+                    .unwrap(),
+                body: (),
+            }
+            .into_response(),
+            FaultKind::ServiceUnavailable => ServiceResponse {
+                comresult: ComResultBuilder::with_status(StatusCode::SERVICE_UNAVAILABLE)
+                    .bereich("WWSVC")
+                    .code("503 Service Unavailable")
+                    .info("Der Dienst ist zurzeit nicht verfügbar.")
+                    .info2("wwsvc-mock: Injected fault")
+                    .build()
+                    .unwrap(),
+                body: (),
+            }
+            .into_response(),
+            FaultKind::ErrorPage => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(header::CONTENT_TYPE, "text/html")],
+                ERROR_PAGE,
+            )
+                .into_response(),
+            FaultKind::TruncatedJson => {
+                let json = serde_json::to_string(&response).unwrap();
+                let mut end = json.len() / 2;
+                while !json.is_char_boundary(end) {
+                    end -= 1;
+                }
+                (
+                    StatusCode::OK,
+                    [(header::CONTENT_TYPE, "application/json")],
+                    json[..end].to_string(),
+                )
+                    .into_response()
+            }
+            FaultKind::DropConnection => {
+                // hyper only buffers the head until the first body chunk, so a body that fails right away closes the
+                // connection before the status line is written
+                let stream = futures_util::stream::once(async {
+                    Err::<Bytes, _>(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionAborted,
+                        "wwsvc-mock: Injected fault",
+                    ))
+                });
+                let mut response = Response::new(Body::from_stream(stream));
+                response.extensions_mut().insert(DroppedConnection);
+                response
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{FaultConfig, FaultKind};
+
+    use super::FaultInjector;
+
+    #[test]
+    fn same_seed_same_faults() {
+        let config = FaultConfig {
+            probability: 0.5,
+            kinds: vec![FaultKind::InternalServerError, FaultKind::TruncatedJson],
+        };
+        let first = FaultInjector::new(Some(42));
+        let second = FaultInjector::new(Some(42));
+        let first = (0..50).map(|_| first.pick(Some(&config))).collect::<Vec<_>>();
+        let second = (0..50).map(|_| second.pick(Some(&config))).collect::<Vec<_>>();
+        assert_eq!(first, second);
+        assert_eq!(first.iter().any(Option::is_some), true);
+        assert_eq!(first.iter().any(Option::is_none), true);
+
+        let injector = FaultInjector::new(Some(42));
+        let before = (0..50).map(|_| injector.pick(Some(&config))).collect::<Vec<_>>();
+        injector.reset();
+        let after = (0..50).map(|_| injector.pick(Some(&config))).collect::<Vec<_>>();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn probability_bounds() {
+        let injector = FaultInjector::new(None);
+        let never = FaultConfig {
+            probability: 0.0,
+            kinds: vec![FaultKind::ErrorPage],
+        };
+        let always = FaultConfig {
+            probability: 1.0,
+            kinds: vec![FaultKind::ErrorPage],
+        };
+        assert_eq!(injector.pick(None), None);
+        assert_eq!(injector.pick(Some(&never)), None);
+        assert_eq!(injector.pick(Some(&always)), Some(FaultKind::ErrorPage));
+    }
+
+    #[tokio::test]
+    async fn truncated_json() {
+        let response = FaultKind::TruncatedJson.respond(crate::routes::ServiceResponse {
+            comresult: crate::routes::ComResultBuilder::with_status(axum::http::StatusCode::OK)
+                .code("200 OK")
+                .info("Kein Fehler")
+                .build()
+                .unwrap(),
+            body: (),
+        });
+        assert_eq!(response.status(), 200);
+        let body = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).is_err(), true);
+    }
+}
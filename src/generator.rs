@@ -0,0 +1,207 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde_json::{json, Map, Value};
+
+use crate::GeneratedEntity;
+
+const PRODUCTS: &[&str] = &[
+    "Sechskantschraube",
+    "Unterlegscheibe",
+    "Kabelbinder",
+    "Schutzhandschuhe",
+    "Akkuschrauber",
+    "Bohrer-Set",
+    "Winkelschleifer",
+    "Schraubendreher",
+    "Maßband",
+    "Wasserwaage",
+    "Arbeitsleuchte",
+    "Verlängerungskabel",
+    "Dübel",
+    "Holzleim",
+    "Schleifpapier",
+];
+const VARIANTS: &[&str] = &[
+    "verzinkt",
+    "Edelstahl",
+    "schwarz",
+    "Profi",
+    "M8 x 40",
+    "300 mm",
+    "5 m",
+    "Größe 9",
+    "18 V",
+    "10-teilig",
+];
+const PRODUCT_GROUPS: &[&str] = &["EISENWAREN", "WERKZEUG", "ELEKTRO", "ARBEITSSCHUTZ", "BEFESTIGUNG"];
+const UNITS: &[&str] = &["Stk", "Pck", "m", "kg", "Set"];
+const NAMES: &[&str] = &[
+    "Müller", "Schmidt", "Schneider", "Fischer", "Weber", "Meyer", "Wagner", "Becker", "Schulz", "Hoffmann", "Koch",
+    "Richter", "Klein", "Wolf", "Schröder",
+];
+const TRADES: &[&str] = &["Metallbau", "Elektrotechnik", "Haustechnik", "Handel", "Bau", "Holzbau", "Logistik"];
+const LEGAL_FORMS: &[&str] = &["GmbH", "GmbH & Co. KG", "AG", "e.K.", "OHG", "KG"];
+const STREETS: &[&str] = &[
+    "Hauptstraße",
+    "Bahnhofstraße",
+    "Gartenweg",
+    "Industriestraße",
+    "Lindenallee",
+    "Schillerstraße",
+    "Goethestraße",
+    "Am Markt",
+    "Gewerbering",
+    "Dorfstraße",
+];
+const CITIES: &[(&str, &str)] = &[
+    ("10115", "Berlin"),
+    ("20095", "Hamburg"),
+    ("80331", "München"),
+    ("50667", "Köln"),
+    ("60311", "Frankfurt am Main"),
+    ("70173", "Stuttgart"),
+    ("40213", "Düsseldorf"),
+    ("04109", "Leipzig"),
+    ("28195", "Bremen"),
+    ("90402", "Nürnberg"),
+    ("01067", "Dresden"),
+    ("30159", "Hannover"),
+];
+const ORDER_STATES: &[&str] = &["offen", "geliefert", "berechnet"];
+
+/// Formats an amount in cents like the WEBWARE does, e.g. `12,95`.
+fn amount(cents: u64) -> String {
+    format!("{},{:02}", cents / 100, cents % 100)
+}
+
+fn pick<'a>(rng: &mut StdRng, values: &[&'a str]) -> &'a str {
+    values.choose(rng).unwrap()
+}
+
+fn company(rng: &mut StdRng) -> String {
+    let name = format!("{} {} {}", pick(rng, NAMES), pick(rng, TRADES), pick(rng, LEGAL_FORMS));
+    name.chars().take(30).collect()
+}
+
+fn vat_id(rng: &mut StdRng) -> String {
+    format!("DE{:09}", rng.gen_range(100_000_000..1_000_000_000u32))
+}
+
+/// Adds the address fields of a customer or supplier with the given field prefix, e.g. `KUN`.
+fn address(rng: &mut StdRng, prefix: &str, record: &mut Map<String, Value>) {
+    let (zip, city) = CITIES.choose(rng).unwrap();
+    let street = format!("{} {}", pick(rng, STREETS), rng.gen_range(1..150));
+    record.insert(format!("{}_11_40", prefix), json!(company(rng)));
+    record.insert(format!("{}_STRASSE", prefix), json!(street));
+    record.insert(format!("{}_PLZ", prefix), json!(zip));
+    record.insert(format!("{}_ORT", prefix), json!(city));
+    record.insert(format!("{}_LAND", prefix), json!("DE"));
+    record.insert(format!("{}_UST_ID", prefix), json!(vat_id(rng)));
+}
+
+fn record(entity: GeneratedEntity, rng: &mut StdRng, index: usize) -> Value {
+    let mut record = Map::new();
+    match entity {
+        GeneratedEntity::Artikel => {
+            let product = pick(rng, PRODUCTS);
+            let reduced_vat = rng.gen_bool(0.1);
+            record.insert("ART_1_25".to_string(), json!(format!("ART{:05}", index)));
+            record.insert(
+                "ART_51_60".to_string(),
+                json!(product.to_uppercase().chars().take(10).collect::<String>()),
+            );
+            record.insert(
+                "ART_BEZEICHNUNG".to_string(),
+                json!(format!("{} {}", product, pick(rng, VARIANTS))),
+            );
+            record.insert("ART_WARENGR".to_string(), json!(pick(rng, PRODUCT_GROUPS)));
+            record.insert("ART_EINHEIT".to_string(), json!(pick(rng, UNITS)));
+            record.insert("ART_PREIS".to_string(), json!(amount(rng.gen_range(19..50_000))));
+            record.insert("ART_MWST".to_string(), json!(if reduced_vat { "2" } else { "1" }));
+        }
+        GeneratedEntity::Kunde => {
+            record.insert("KUN_1_10".to_string(), json!((10_000 + index).to_string()));
+            address(rng, "KUN", &mut record);
+            record.insert("KUN_MWST".to_string(), json!("1"));
+        }
+        GeneratedEntity::Lieferant => {
+            record.insert("LIF_1_10".to_string(), json!((70_000 + index).to_string()));
+            address(rng, "LIF", &mut record);
+        }
+        GeneratedEntity::Auftrag => {
+            let net = rng.gen_range(500..500_000u64);
+            let vat = (net * 19 + 50) / 100;
+            record.insert("AUF_1_10".to_string(), json!((200_000 + index).to_string()));
+            record.insert("AUF_KUNDE".to_string(), json!(rng.gen_range(10_001..10_100).to_string()));
+            record.insert(
+                "AUF_DATUM".to_string(),
+                json!(format!("{:02}.{:02}.2024", rng.gen_range(1..=28), rng.gen_range(1..=12))),
+            );
+            record.insert("AUF_NETTO".to_string(), json!(amount(net)));
+            record.insert("AUF_MWST".to_string(), json!(amount(vat)));
+            record.insert("AUF_BRUTTO".to_string(), json!(amount(net + vat)));
+            record.insert("AUF_STATUS".to_string(), json!(pick(rng, ORDER_STATES)));
+        }
+    }
+    record.insert("SNR".to_string(), json!(index.to_string()));
+    Value::Object(record)
+}
+
+/// Generates a list response with `count` records of the entity, e.g.
+/// `{"ARTIKELLISTE": {"ANZAHL": "2", "ARTIKEL": [...]}}`. The same seed always generates the same records.
+pub(crate) fn generate(entity: GeneratedEntity, count: usize, seed: u64) -> Value {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let records = (1..=count)
+        .map(|index| record(entity, &mut rng, index))
+        .collect::<Vec<_>>();
+    json!({
+        entity.list_name(): {
+            "ANZAHL": count.to_string(),
+            entity.item_name(): records,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::GeneratedEntity;
+
+    #[test]
+    fn generate_deterministically() {
+        let body = super::generate(GeneratedEntity::Artikel, 3, 42);
+        assert_eq!(body, super::generate(GeneratedEntity::Artikel, 3, 42));
+        assert_ne!(body, super::generate(GeneratedEntity::Artikel, 3, 43));
+
+        assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "3");
+        let artikel = body["ARTIKELLISTE"]["ARTIKEL"].as_array().unwrap();
+        assert_eq!(artikel.len(), 3);
+        assert_eq!(artikel[2]["ART_1_25"], "ART00003");
+        assert_eq!(artikel[2]["SNR"], "3");
+        assert!(artikel.iter().all(|artikel| artikel["ART_51_60"].as_str().unwrap().chars().count() <= 10));
+    }
+
+    #[test]
+    fn generate_entities() {
+        let body = super::generate(GeneratedEntity::Kunde, 1, 0);
+        let kunde = &body["KUNDENLISTE"]["KUNDE"][0];
+        assert_eq!(kunde["KUN_1_10"], "10001");
+        assert_eq!(kunde["KUN_LAND"], "DE");
+        assert_eq!(kunde["KUN_UST_ID"].as_str().unwrap().len(), 11);
+
+        let body = super::generate(GeneratedEntity::Lieferant, 1, 0);
+        assert_eq!(body["LIEFERANTENLISTE"]["LIEFERANT"][0]["LIF_1_10"], "70001");
+
+        let body = super::generate(GeneratedEntity::Auftrag, 5, 0);
+        for auftrag in body["AUFTRAGSLISTE"]["AUFTRAG"].as_array().unwrap() {
+            let cents = |field: &str| auftrag[field].as_str().unwrap().replace(',', "").parse::<u64>().unwrap();
+            assert_eq!(cents("AUF_NETTO") + cents("AUF_MWST"), cents("AUF_BRUTTO"));
+        }
+    }
+
+    #[test]
+    fn format_amounts() {
+        assert_eq!(super::amount(1295), "12,95");
+        assert_eq!(super::amount(5), "0,05");
+    }
+}
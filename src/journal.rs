@@ -0,0 +1,159 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+};
+
+use axum::http::HeaderMap;
+use serde::Serialize;
+
+use crate::diagnostics::NearMiss;
+
+/// The default number of requests kept in the journal.
+pub(crate) const DEFAULT_CAPACITY: usize = 1000;
+
+/// The route a request was received on.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum RequestRoute {
+    /// `GET /WWSVC/WWSERVICE/REGISTER/...`
+    #[serde(rename = "REGISTER")]
+    Register,
+    /// `GET /WWSVC/WWSERVICE/DEREGISTER/...`
+    #[serde(rename = "DEREGISTER")]
+    Deregister,
+    /// `PUT/POST/DELETE /WWSVC/EXECJSON`
+    #[serde(rename = "EXECJSON")]
+    ExecJson,
+}
+
+/// A request that was received by the mock server, as stored in the journal.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ReceivedRequest {
+    /// The route the request was received on.
+    pub route: RequestRoute,
+    /// The full function name including the method, e.g. `ARTIKEL.PUT`. Only set for EXECJSON requests.
+    pub function: Option<String>,
+    /// The requested revision of the function (EXECJSON) or of the webservices (REGISTER).
+    pub revision: Option<u32>,
+    /// The parameters of the function. Only set for EXECJSON requests.
+    pub parameters: BTreeMap<String, String>,
+    /// The request headers, with lowercase names.
+    pub headers: BTreeMap<String, String>,
+    /// The service pass the request was made with, or for REGISTER, the service pass that was issued.
+    pub service_pass: Option<String>,
+    /// The mock resource or responder that answered the request, if any. Requests the proxy forwarded are answered by
+    /// the `upstream WEBWARE`.
+    pub matched_resource: Option<String>,
+    /// If no mock resource or stateful function answered the request, the mock resources that came closest to matching it.
+    pub near_misses: Option<Vec<NearMiss>>,
+    /// The HTTP status code of the response, or `0` if the connection was dropped before a response was sent.
+    pub status: u16,
+}
+
+impl ReceivedRequest {
+    pub(crate) fn new(route: RequestRoute, headers: &HeaderMap) -> Self {
+        ReceivedRequest {
+            route,
+            function: None,
+            revision: None,
+            parameters: BTreeMap::new(),
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            service_pass: None,
+            matched_resource: None,
+            near_misses: None,
+            status: 0,
+        }
+    }
+
+    /// Returns the value of the given parameter, if it was sent.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.get(name).map(String::as_str)
+    }
+}
+
+/// A bounded journal of received requests. Once full, the oldest requests are discarded.
+#[derive(Debug)]
+pub(crate) struct Journal {
+    capacity: usize,
+    requests: Mutex<VecDeque<ReceivedRequest>>,
+}
+
+impl Journal {
+    pub fn new(capacity: usize) -> Self {
+        Journal {
+            capacity,
+            requests: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn record(&self, request: ReceivedRequest) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut requests = self.requests.lock().unwrap();
+        while requests.len() >= self.capacity {
+            requests.pop_front();
+        }
+        requests.push_back(request);
+    }
+
+    /// Returns all requests in the journal, oldest first.
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.requests.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+    use pretty_assertions::assert_eq;
+
+    use super::{Journal, ReceivedRequest, RequestRoute};
+
+    fn request(function: &str) -> ReceivedRequest {
+        let mut request = ReceivedRequest::new(RequestRoute::ExecJson, &HeaderMap::new());
+        request.function = Some(function.to_string());
+        request
+    }
+
+    #[test]
+    fn journal_is_bounded() {
+        let journal = Journal::new(2);
+        journal.record(request("A.GET"));
+        journal.record(request("B.GET"));
+        journal.record(request("C.GET"));
+        let functions = journal
+            .requests()
+            .into_iter()
+            .map(|request| request.function.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(functions, vec!["B.GET", "C.GET"]);
+
+        journal.clear();
+        assert_eq!(journal.requests().len(), 0);
+
+        let journal = Journal::new(0);
+        journal.record(request("A.GET"));
+        assert_eq!(journal.requests().len(), 0);
+    }
+
+    #[test]
+    fn headers_and_parameters() {
+        let mut headers = HeaderMap::new();
+        headers.insert("WWSVC-REQID", HeaderValue::from_static("1"));
+        let mut request = ReceivedRequest::new(RequestRoute::ExecJson, &headers);
+        request.parameters.insert("ARTNR".to_string(), "A1".to_string());
+        assert_eq!(request.headers["wwsvc-reqid"], "1");
+        assert_eq!(request.parameter("ARTNR"), Some("A1"));
+        assert_eq!(request.parameter("FELDER"), None);
+    }
+}
@@ -11,23 +11,102 @@ use axum::{
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
-    routing::{get, put},
+    routing::{get, post, put},
     Router,
 };
 use http_body_util::BodyExt;
 
 mod app_config;
+mod builder;
+mod cassette;
+mod counters;
+mod cursors;
+mod dataset;
+mod diagnostics;
+mod entity_store;
+mod faults;
+mod generator;
+mod journal;
+mod mock_registry;
+#[cfg(feature = "proxy")]
+mod proxy;
+mod responders;
 mod routes;
+mod schema;
+#[cfg(feature = "scripting")]
+mod scripting;
+mod server;
+mod sessions;
+mod template;
 
-pub use app_config::{AppConfig, FileOrString, MockResource, MockResourceMethod, ServerConfig, WebwareConfig, WebservicesConfig, CredentialsConfig};
+pub use app_config::{AppConfig, FileOrString, GeneratedEntity, MockResource, MockResourceMethod, MockResourceRevision, ParameterMatcher, NumericRange, DatasetConfig, FieldFilter, FilterMode, ServerConfig, WebwareConfig, WebservicesConfig, CredentialsConfig, SessionsConfig, ApplicationConfig, StatefulFunction, ResponseSequence, SequenceMode, ComResultConfig, LatencyConfig, Jitter, FaultConfig, FaultKind, ProxyConfig, Cassette, CassetteInteraction, FunctionSchema, FieldSchema, FieldType};
+pub use builder::{mock, MockResourceBuilder};
+pub use diagnostics::NearMiss;
+pub use journal::{ReceivedRequest, RequestRoute};
+pub use responders::{RegisteredResponder, Responder};
+pub use server::MockServer;
+pub use routes::{
+    ComResult, ComResultBuilder, ServiceResponse, WebserviceFunction, WebserviceParameter, WebservicePassInfo,
+    WebserviceRequest,
+};
 use routes::{
+    admin::{
+        add_mock, clear_mocks, clear_requests, get_mock, list_mocks, list_requests, remove_mock,
+        replace_mock, reset,
+    },
     exec_json::exec_json,
     service_pass::{handle_deregister, handle_register},
 };
+use cassette::Replayer;
+use counters::CallCounters;
+use cursors::CursorRegistry;
+use entity_store::EntityStore;
+use faults::FaultInjector;
+use journal::Journal;
+use mock_registry::MockRegistry;
+#[cfg(feature = "proxy")]
+use proxy::Proxy;
+use schema::SchemaRegistry;
+#[cfg(feature = "scripting")]
+use scripting::ScriptEngine;
+use sessions::SessionRegistry;
 
 #[derive(axum::extract::FromRef, Clone)]
 struct AppState {
     pub config: Arc<AppConfig>,
+    pub sessions: Arc<SessionRegistry>,
+    pub store: Arc<EntityStore>,
+    pub counters: Arc<CallCounters>,
+    pub cursors: Arc<CursorRegistry>,
+    pub faults: Arc<FaultInjector>,
+    pub mocks: Arc<MockRegistry>,
+    pub journal: Arc<Journal>,
+    #[cfg(feature = "proxy")]
+    pub proxy: Option<Arc<Proxy>>,
+    pub cassette: Option<Arc<Replayer>>,
+    #[cfg(feature = "scripting")]
+    pub scripts: Arc<ScriptEngine>,
+    pub schemas: Arc<SchemaRegistry>,
+}
+
+impl AppState {
+    /// Checks that the scripts of a mock resource compile and that its data matches the schemas of its function.
+    fn validate_resource(&self, resource: &MockResource) -> anyhow::Result<()> {
+        let sequence = resource.sequence.iter().flat_map(|sequence| &sequence.responses);
+        for data_source in std::iter::once(&resource.data_source).chain(sequence) {
+            #[cfg(feature = "scripting")]
+            self.scripts
+                .validate(data_source)
+                .map_err(|err| anyhow::anyhow!("Invalid script in {}: {:#}", resource, err))?;
+            #[cfg(not(feature = "scripting"))]
+            if matches!(data_source, FileOrString::Script { .. }) {
+                anyhow::bail!("{} responds with a script, which requires the `scripting` feature", resource);
+            }
+        }
+        self.schemas
+            .validate_resource(resource)
+            .map_err(|err| anyhow::anyhow!("Data of {} doesn't match the schema: {:#}", resource, err))
+    }
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -85,7 +164,73 @@ pub struct OptionalJson(
 /// - `PUT/POST/DELETE /WWSVC/EXECJSON`
 /// - `GET /WWSVC/WWSERVICE/REGISTER/:vendor_hash/:app_hash/:secret/:revision/`
 /// - `GET /WWSVC/WWSERVICE/DEREGISTER/:service_pass/`
+///
+/// If the [admin API][AppConfig::admin_api] is enabled, it also supports the following routes:
+///
+/// - `GET/POST/DELETE /__admin/mocks`
+/// - `GET/PUT/DELETE /__admin/mocks/:id`
+/// - `GET/DELETE /__admin/requests`
+/// - `POST /__admin/reset`
 pub async fn app(config: &AppConfig) -> anyhow::Result<Router> {
+    app_with_handle(config).await.map(|(router, _)| router)
+}
+
+/// A handle to the state of a mock server, e.g. to verify which requests it received.
+///
+/// Created together with the router by [app_with_handle].
+#[derive(Debug, Clone)]
+pub struct MockHandle {
+    journal: Arc<Journal>,
+    cassette: Option<Arc<Replayer>>,
+}
+
+impl MockHandle {
+    /// Returns the requests the server received, oldest first.
+    ///
+    /// Only the last [journal_capacity][AppConfig::journal_capacity] requests are kept.
+    pub fn received_requests(&self) -> Vec<ReceivedRequest> {
+        self.journal.requests()
+    }
+
+    /// Returns the EXECJSON requests the server received for the given function, e.g. `ARTIKEL.PUT`, oldest first.
+    pub fn received_requests_for(&self, function: &str) -> Vec<ReceivedRequest> {
+        self.journal
+            .requests()
+            .into_iter()
+            .filter(|request| {
+                request
+                    .function
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(function))
+            })
+            .collect()
+    }
+
+    /// Returns the EXECJSON requests that no mock resource or stateful function answered, oldest first.
+    ///
+    /// Each request contains the [mock resources that came closest][ReceivedRequest::near_misses] to matching it.
+    pub fn unmatched_requests(&self) -> Vec<ReceivedRequest> {
+        self.journal
+            .requests()
+            .into_iter()
+            .filter(|request| request.near_misses.is_some())
+            .collect()
+    }
+
+    /// Returns the number of [cassette][AppConfig::cassette] interactions that haven't been replayed yet, or `None` if
+    /// there is no cassette.
+    pub fn remaining_interactions(&self) -> Option<usize> {
+        self.cassette.as_ref().map(|cassette| cassette.remaining())
+    }
+
+    /// Clears the journal of received requests.
+    pub fn reset_journal(&self) {
+        self.journal.clear();
+    }
+}
+
+/// Generates the router for the mock server like [app], along with a [MockHandle] to inspect its state.
+pub async fn app_with_handle(config: &AppConfig) -> anyhow::Result<(Router, MockHandle)> {
     let registering_routes = Router::new()
         .route(
             "/REGISTER/:vendor_hash/:app_hash/:secret/:revision/",
@@ -104,17 +249,70 @@ pub async fn app(config: &AppConfig) -> anyhow::Result<Router> {
         )
         .nest("/WWSERVICE", registering_routes);
 
-    let mut router = Router::new()
-        .nest("/WWSVC", wwsvc_router)
-        .with_state(AppState {
-            config: Arc::new(config.clone()),
-        });
+    let faults = FaultInjector::new(config.seed);
+    if config.faults.is_some() || config.mock_resources.iter().any(|resource| resource.faults.is_some()) {
+        tracing::info!("Fault injection seed: {}", faults.seed());
+    }
+
+    let mut router = Router::new().nest("/WWSVC", wwsvc_router);
+
+    if config.admin_api {
+        let admin_router = Router::new()
+            .route("/mocks", get(list_mocks).post(add_mock).delete(clear_mocks))
+            .route("/mocks/:id", get(get_mock).put(replace_mock).delete(remove_mock))
+            .route("/requests", get(list_requests).delete(clear_requests))
+            .route("/reset", post(reset));
+        router = router.nest("/__admin", admin_router);
+    }
+
+    let journal = Arc::new(Journal::new(
+        config.journal_capacity.unwrap_or(journal::DEFAULT_CAPACITY),
+    ));
+
+    let cassette = match &config.cassette {
+        Some(cassette) => Some(Arc::new(Replayer::new(cassette)?)),
+        None => None,
+    };
+
+    #[cfg(not(feature = "proxy"))]
+    if config.proxy.is_some() {
+        anyhow::bail!("The proxy mode requires the `proxy` feature");
+    }
+
+    let schemas = SchemaRegistry::new(config.load_schemas()?);
+
+    let state = AppState {
+        config: Arc::new(config.clone()),
+        sessions: Arc::new(SessionRegistry::new(&config.webware)),
+        store: Arc::new(EntityStore::new(&config.stateful_functions)?),
+        counters: Arc::new(CallCounters::default()),
+        cursors: Arc::new(CursorRegistry::default()),
+        faults: Arc::new(faults),
+        mocks: Arc::new(MockRegistry::new(&config.mock_resources)),
+        journal: journal.clone(),
+        #[cfg(feature = "proxy")]
+        proxy: config.proxy.as_ref().map(|proxy| Arc::new(Proxy::new(proxy))),
+        cassette: cassette.clone(),
+        #[cfg(feature = "scripting")]
+        scripts: Arc::new(ScriptEngine::new()),
+        schemas: Arc::new(schemas),
+    };
+    let application_resources = config
+        .webware
+        .applications
+        .iter()
+        .flat_map(|application| application.mock_resources.iter().flatten());
+    for resource in config.mock_resources.iter().chain(application_resources) {
+        state.validate_resource(resource)?;
+    }
+
+    let mut router = router.with_state(state);
 
     if config.debug {
         router = router.layer(axum::middleware::from_fn(logging_middleware));
     }
 
-    Ok(router)
+    Ok((router, MockHandle { journal, cassette }))
 }
 
 /// A wrapper for `regex::Regex` that deserializes from a string.
@@ -51,6 +51,12 @@ async fn main() -> anyhow::Result<()> {
         server_config.bind_address
     );
     tracing::info!("Mocked Resources: {}", config.mock_resources.len());
+    if config.admin_api {
+        tracing::info!(
+            "Admin API: http://{}/__admin/",
+            server_config.bind_address
+        );
+    }
     tracing::info!("Vendor Hash: {}", config.webware.webservices.vendor_hash);
     tracing::info!(
         "Application Hash: {}",
@@ -62,11 +68,38 @@ async fn main() -> anyhow::Result<()> {
         config.webware.webservices.application_secret
     );
     tracing::info!("--------- Credentials ---------");
-    tracing::info!("Service Pass: {}", config.webware.credentials.service_pass);
-    tracing::info!(
-        "Application ID: {}",
-        config.webware.credentials.application_id
-    );
+    if config.webware.sessions.enabled {
+        tracing::info!("Service passes are issued per REGISTER");
+    } else {
+        tracing::info!("Service Pass: {}", config.webware.credentials.service_pass);
+        tracing::info!(
+            "Application ID: {}",
+            config.webware.credentials.application_id
+        );
+    }
+    for application in &config.webware.applications {
+        tracing::info!("-------- Application ----------");
+        if let Some(name) = &application.name {
+            tracing::info!("Name: {}", name);
+        }
+        tracing::info!("Vendor Hash: {}", application.webservices.vendor_hash);
+        tracing::info!(
+            "Application Hash: {}",
+            application.webservices.application_hash
+        );
+        tracing::info!("Revision: {}", application.webservices.version);
+        tracing::info!(
+            "Application Secret: {}",
+            application.webservices.application_secret
+        );
+        if !config.webware.sessions.enabled {
+            tracing::info!("Service Pass: {}", application.credentials.service_pass);
+            tracing::info!(
+                "Application ID: {}",
+                application.credentials.application_id
+            );
+        }
+    }
     tracing::info!("-------------------------------");
 
     let app = app(&config).await?;
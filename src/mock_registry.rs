@@ -0,0 +1,169 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::MockResource;
+
+/// Returns the key that calls to the global mock resource with the given ID are counted by.
+pub(crate) fn counter_key(id: usize) -> String {
+    format!("mock {}", id)
+}
+
+/// A mock resource registered at runtime, along with the ID it can be addressed by.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct MockEntry {
+    pub id: usize,
+    #[serde(flatten)]
+    pub resource: MockResource,
+}
+
+#[derive(Debug)]
+struct Entries {
+    next_id: usize,
+    entries: Vec<MockEntry>,
+}
+
+impl Entries {
+    fn new(resources: &[MockResource]) -> Self {
+        let mut entries = Entries {
+            next_id: 1,
+            entries: vec![],
+        };
+        for resource in resources {
+            entries.add(resource.clone());
+        }
+        entries
+    }
+
+    fn add(&mut self, resource: MockResource) -> MockEntry {
+        let entry = MockEntry {
+            id: self.next_id,
+            resource,
+        };
+        self.next_id += 1;
+        self.entries.push(entry.clone());
+        entry
+    }
+}
+
+/// Holds the global mock resources, which can be changed at runtime through the admin API.
+#[derive(Debug)]
+pub(crate) struct MockRegistry {
+    initial: Vec<MockResource>,
+    entries: Mutex<Entries>,
+}
+
+impl MockRegistry {
+    pub fn new(resources: &[MockResource]) -> Self {
+        MockRegistry {
+            initial: resources.to_vec(),
+            entries: Mutex::new(Entries::new(resources)),
+        }
+    }
+
+    /// Returns a snapshot of the IDs and the current mock resources, in the order they were added.
+    pub fn snapshot(&self) -> (Vec<usize>, Vec<MockResource>) {
+        self.entries
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .map(|entry| (entry.id, entry.resource.clone()))
+            .unzip()
+    }
+
+    pub fn list(&self) -> Vec<MockEntry> {
+        self.entries.lock().unwrap().entries.clone()
+    }
+
+    pub fn get(&self, id: usize) -> Option<MockEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .cloned()
+    }
+
+    pub fn add(&self, resource: MockResource) -> MockEntry {
+        self.entries.lock().unwrap().add(resource)
+    }
+
+    /// Replaces the mock resource with the given ID, returning `None` if there is no such resource.
+    pub fn replace(&self, id: usize, resource: MockResource) -> Option<MockEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entries.iter_mut().find(|entry| entry.id == id)?;
+        entry.resource = resource;
+        Some(entry.clone())
+    }
+
+    /// Removes the mock resource with the given ID, returning `None` if there is no such resource.
+    pub fn remove(&self, id: usize) -> Option<MockEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        let index = entries.entries.iter().position(|entry| entry.id == id)?;
+        Some(entries.entries.remove(index))
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().entries.clear();
+    }
+
+    /// Restores the mock resources from the configuration, discarding every change made at runtime.
+    pub fn reset(&self) {
+        *self.entries.lock().unwrap() = Entries::new(&self.initial);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{FileOrString, MockResource, MockResourceMethod};
+
+    use super::MockRegistry;
+
+    fn resource(function: &str) -> MockResource {
+        MockResource {
+            data_source: FileOrString::Empty,
+            function: function.to_string(),
+            method: MockResourceMethod::Get,
+            revision: 1.into(),
+            parameters: None,
+            ..Default::default()
+        }
+    }
+
+    fn functions(registry: &MockRegistry) -> Vec<String> {
+        registry
+            .snapshot()
+            .1
+            .into_iter()
+            .map(|resource| resource.function)
+            .collect()
+    }
+
+    #[test]
+    fn add_replace_remove_reset() {
+        let registry = MockRegistry::new(&[resource("ARTIKEL")]);
+        let added = registry.add(resource("KUNDE"));
+        assert_eq!(added.id, 2);
+        assert_eq!(functions(&registry), vec!["ARTIKEL", "KUNDE"]);
+
+        assert_eq!(registry.replace(2, resource("LIEFERANT")).is_some(), true);
+        assert_eq!(registry.get(2).unwrap().resource.function, "LIEFERANT");
+        assert_eq!(registry.replace(3, resource("AUFTRAG")).is_none(), true);
+
+        assert_eq!(registry.remove(1).unwrap().resource.function, "ARTIKEL");
+        assert_eq!(registry.remove(1).is_none(), true);
+        assert_eq!(functions(&registry), vec!["LIEFERANT"]);
+
+        registry.clear();
+        assert_eq!(registry.list().len(), 0);
+        assert_eq!(registry.add(resource("AUFTRAG")).id, 3);
+
+        registry.reset();
+        assert_eq!(functions(&registry), vec!["ARTIKEL"]);
+        assert_eq!(registry.list()[0].id, 1);
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
+
+use anyhow::Context;
+use axum::http::{Method, StatusCode};
+use encoding_rs::WINDOWS_1252;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::{
+    routes::{ComResult, WebserviceRequest},
+    ComResultConfig, FileOrString, MockResource, ParameterMatcher, ProxyConfig,
+};
+
+/// The file in the record directory that recorded mock resources are appended to.
+const RECORDED_RESOURCES_FILE: &str = "mock_resources.toml";
+
+/// The service pass the proxy received from the upstream WEBWARE.
+#[derive(Debug, Clone)]
+struct UpstreamSession {
+    service_pass: String,
+    application_id: String,
+}
+
+/// A response of the upstream WEBWARE, split into the COMRESULT and the remaining body.
+#[derive(Debug, Clone)]
+pub(crate) struct UpstreamResponse {
+    pub comresult: ComResult,
+    pub body: Option<Value>,
+}
+
+/// Forwards EXECJSON requests to an upstream WEBWARE and records the exchanges.
+#[derive(Debug)]
+pub(crate) struct Proxy {
+    config: ProxyConfig,
+    client: reqwest::Client,
+    session: Mutex<Option<UpstreamSession>>,
+    request_id: AtomicUsize,
+}
+
+impl Proxy {
+    pub fn new(config: &ProxyConfig) -> Self {
+        Proxy {
+            config: config.clone(),
+            client: reqwest::Client::new(),
+            session: Mutex::new(None),
+            request_id: AtomicUsize::new(0),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.config.url.trim_end_matches('/'), path)
+    }
+
+    async fn register(&self) -> anyhow::Result<UpstreamSession> {
+        let webservices = &self.config.webservices;
+        let body: Value = self
+            .client
+            .get(self.url(&format!(
+                "WWSVC/WWSERVICE/REGISTER/{}/{}/{}/{}/",
+                webservices.vendor_hash,
+                webservices.application_hash,
+                webservices.application_secret,
+                webservices.version
+            )))
+            .send()
+            .await?
+            .json()
+            .await
+            .context("Upstream REGISTER response isn't valid JSON")?;
+
+        match (
+            body["SERVICEPASS"]["PASSID"].as_str(),
+            body["SERVICEPASS"]["APPID"].as_str(),
+        ) {
+            (Some(service_pass), Some(application_id)) => Ok(UpstreamSession {
+                service_pass: service_pass.to_string(),
+                application_id: application_id.to_string(),
+            }),
+            _ => anyhow::bail!("Upstream REGISTER failed: {}", body["COMRESULT"]),
+        }
+    }
+
+    /// Returns the current upstream session, registering first if there is none or if `renew` is set.
+    async fn session(&self, renew: bool) -> anyhow::Result<UpstreamSession> {
+        let mut session = self.session.lock().await;
+        match &*session {
+            Some(current) if !renew => Ok(current.clone()),
+            _ => {
+                let registered = self.register().await?;
+                *session = Some(registered.clone());
+                Ok(registered)
+            }
+        }
+    }
+
+    /// Sends the request to the upstream WEBWARE, re-signed with the upstream session.
+    async fn send(
+        &self,
+        session: &UpstreamSession,
+        method: &Method,
+        request: &WebserviceRequest,
+    ) -> anyhow::Result<reqwest::Response> {
+        let ts = httpdate::fmt_http_date(SystemTime::now());
+        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let mut request = request.clone();
+        request.pass_info.service_pass = session.service_pass.clone();
+        request.pass_info.app_hash = self.config.webservices.application_hash.clone();
+        request.pass_info.timestamp = ts.clone();
+        request.pass_info.request_id = request_id;
+
+        let pre_hash = format!("{}{}", session.application_id, ts);
+        let (cow, _, _) = WINDOWS_1252.encode(pre_hash.as_str());
+        let hash = format!("{:x}", md5::compute(cow));
+
+        Ok(self
+            .client
+            .request(method.clone(), self.url("WWSVC/EXECJSON"))
+            .header("WWSVC-EXECUTE-MODE", &request.pass_info.execute_mode)
+            .header("WWSVC-REQID", request_id.to_string())
+            .header("WWSVC-TS", &ts)
+            .header("WWSVC-HASH", hash)
+            .json(&request)
+            .send()
+            .await?)
+    }
+
+    /// Forwards the request to the upstream WEBWARE.
+    ///
+    /// If the upstream WEBWARE doesn't know the service pass (anymore), the proxy registers again and retries once.
+    pub async fn forward(
+        &self,
+        method: &Method,
+        request: &WebserviceRequest,
+    ) -> anyhow::Result<UpstreamResponse> {
+        let session = self.session(false).await?;
+        let mut response = self.send(&session, method, request).await?;
+        if [StatusCode::NOT_FOUND, StatusCode::UNAUTHORIZED].contains(&response.status()) {
+            let session = self.session(true).await?;
+            response = self.send(&session, method, request).await?;
+        }
+
+        let mut body: Value = response
+            .json()
+            .await
+            .context("Upstream EXECJSON response isn't valid JSON")?;
+        let comresult = body
+            .as_object_mut()
+            .and_then(|body| body.remove("COMRESULT"))
+            .context("Upstream EXECJSON response has no COMRESULT")?;
+        let comresult: ComResult = serde_json::from_value(comresult)?;
+
+        let body = match body {
+            Value::Object(body) if body.is_empty() => None,
+            body => Some(body),
+        };
+        Ok(UpstreamResponse { comresult, body })
+    }
+
+    /// Records the exchange as a mock resource, if a record directory is configured.
+    pub fn record(
+        &self,
+        request: &WebserviceRequest,
+        response: &UpstreamResponse,
+    ) -> anyhow::Result<Option<MockResource>> {
+        let Some(directory) = &self.config.record_directory else {
+            return Ok(None);
+        };
+        record(&PathBuf::from(directory), request, response).map(Some)
+    }
+}
+
+#[derive(Serialize)]
+struct RecordedResources<'a> {
+    mock_resources: [&'a MockResource; 1],
+}
+
+/// Writes the data of the exchange into the `data` subdirectory and appends a matching mock resource to the
+/// recorded resources file. Parameters are matched exactly.
+fn record(
+    directory: &std::path::Path,
+    request: &WebserviceRequest,
+    response: &UpstreamResponse,
+) -> anyhow::Result<MockResource> {
+    let (function, method) = request
+        .function_and_method()
+        .context("Can't record a request without a valid function name")?;
+
+    let data_source = match &response.body {
+        None => FileOrString::Empty,
+        Some(body) => {
+            let data_directory = directory.join("data");
+            std::fs::create_dir_all(&data_directory)?;
+            let stem = format!("{}_{}_{}", function, method, request.function.revision).to_lowercase();
+            let path = (1..)
+                .map(|n| data_directory.join(format!("{}_{}.json", stem, n)))
+                .find(|path| !path.exists())
+                .unwrap();
+            std::fs::write(&path, serde_json::to_string_pretty(body)?)?;
+            FileOrString::File {
+                file: path.to_string_lossy().to_string(),
+            }
+        }
+    };
+
+    let parameters = request
+        .function
+        .parameter
+        .iter()
+        .map(|parameter| (parameter.name.clone(), ParameterMatcher::exact(&parameter.value)))
+        .collect::<HashMap<_, _>>();
+
+    let resource = MockResource {
+        data_source,
+        function: function.to_string(),
+        method,
+        revision: request.function.revision.into(),
+        parameters: match parameters.is_empty() {
+            true => None,
+            false => Some(parameters),
+        },
+        comresult: match response.comresult.status {
+            200 => None,
+            _ => Some(ComResultConfig::from(&response.comresult)),
+        },
+        ..Default::default()
+    };
+
+    let toml = toml::to_string(&RecordedResources {
+        mock_resources: [&resource],
+    })?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(directory.join(RECORDED_RESOURCES_FILE))?;
+    writeln!(file, "{}", toml)?;
+
+    Ok(resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{
+        providers::{Format, Toml},
+        Figment,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::{
+        routes::{ComResultBuilder, WebserviceRequest},
+        AppConfig, MockResourceMethod,
+    };
+
+    use super::UpstreamResponse;
+
+    fn request(function_name: &str, parameters: &[(&str, &str)]) -> WebserviceRequest {
+        WebserviceRequest::for_test(function_name, 3, parameters)
+    }
+
+    #[test]
+    fn record_exchanges() {
+        let directory = std::env::temp_dir().join(format!("wwsvc-mock-record-{}", crate::app_config::generate_hash()));
+
+        let ok = UpstreamResponse {
+            comresult: ComResultBuilder::with_status(axum::http::StatusCode::OK)
+                .code("200 OK")
+                .info("Kein Fehler")
+                .build()
+                .unwrap(),
+            body: Some(json!({ "ARTIKELLISTE": { "ANZAHL": "0", "ARTIKEL": [] } })),
+        };
+        let resource = super::record(&directory, &request("ARTIKEL.GET", &[("ARTNR", "A.1")]), &ok).unwrap();
+        assert_eq!(resource.method, MockResourceMethod::Get);
+        assert_eq!(resource.parameters.as_ref().unwrap()["ARTNR"].is_match("A.1"), true);
+        assert_eq!(resource.parameters.as_ref().unwrap()["ARTNR"].is_match("AX1"), false);
+        assert_eq!(resource.comresult, None);
+        assert_eq!(resource.data_source.read_json().unwrap(), ok.body);
+
+        let error = UpstreamResponse {
+            comresult: ComResultBuilder::with_status(axum::http::StatusCode::CONFLICT)
+                .code("409 Conflict")
+                .info("Artikel existiert bereits")
+                .build()
+                .unwrap(),
+            body: None,
+        };
+        let resource = super::record(&directory, &request("ARTIKEL.INSERT", &[]), &error).unwrap();
+        assert_eq!(resource.comresult.unwrap().status, 409);
+
+        let config: AppConfig = Figment::from(Toml::file(directory.join(super::RECORDED_RESOURCES_FILE)))
+            .extract()
+            .unwrap();
+        assert_eq!(config.mock_resources.len(), 2);
+        assert_eq!(config.mock_resources[0].data_source.read_json().unwrap(), ok.body);
+        assert_eq!(config.mock_resources[1].method, MockResourceMethod::Insert);
+        assert_eq!(config.mock_resources[1].comresult.as_ref().unwrap().info, "Artikel existiert bereits");
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,96 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::{routes::ServiceResponse, MockResourceMethod, OptionalJson, WebserviceRequest};
+
+/// Computes the response to an EXECJSON request in Rust code, next to the static [mock resources][crate::MockResource].
+///
+/// It is implemented for all closures taking a [WebserviceRequest] and returning a [ServiceResponse], so responders
+/// can capture arguments, e.g. into a channel, or panic on unexpected input.
+/// See [AppConfig::with_responder][crate::AppConfig::with_responder] for more information.
+pub trait Responder: Send + Sync {
+    /// Returns the response to the request.
+    fn respond(&self, request: &WebserviceRequest) -> ServiceResponse<OptionalJson>;
+}
+
+impl<F> Responder for F
+where
+    F: Fn(&WebserviceRequest) -> ServiceResponse<OptionalJson> + Send + Sync,
+{
+    fn respond(&self, request: &WebserviceRequest) -> ServiceResponse<OptionalJson> {
+        self(request)
+    }
+}
+
+/// A [Responder] registered for a function and method, e.g. `ARTIKEL` and [MockResourceMethod::Get].
+#[derive(Clone)]
+pub struct RegisteredResponder {
+    /// The function name, e.g. `ARTIKEL`.
+    pub function: String,
+    /// The method, see [MockResourceMethod].
+    pub method: MockResourceMethod,
+    /// The responder answering the requests.
+    pub responder: Arc<dyn Responder>,
+}
+
+impl RegisteredResponder {
+    /// Returns whether the responder answers the request. The revision and parameters are up to the responder.
+    pub(crate) fn matches(&self, request: &WebserviceRequest) -> bool {
+        request
+            .function_and_method()
+            .is_some_and(|(function, method)| function == self.function && method == self.method)
+    }
+}
+
+impl Display for RegisteredResponder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Responder {{ function: {}, method: {} }}", self.function, self.method)
+    }
+}
+
+impl std::fmt::Debug for RegisteredResponder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredResponder")
+            .field("function", &self.function)
+            .field("method", &self.method)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::{
+        routes::{ServiceResponse, WebserviceRequest},
+        MockResourceMethod,
+    };
+
+    use super::RegisteredResponder;
+
+    fn request(function_name: &str) -> WebserviceRequest {
+        WebserviceRequest::for_test(function_name, 1, &[])
+    }
+
+    #[test]
+    fn match_and_respond() {
+        let responder = RegisteredResponder {
+            function: "ARTIKEL".to_string(),
+            method: MockResourceMethod::Get,
+            responder: Arc::new(|request: &WebserviceRequest| {
+                ServiceResponse::ok(json!({ "FUNCTION": request.function.function_name }))
+            }),
+        };
+
+        assert_eq!(responder.matches(&request("ARTIKEL.GET")), true);
+        assert_eq!(responder.matches(&request("ARTIKEL.PUT")), false);
+        assert_eq!(responder.matches(&request("KUNDE.GET")), false);
+        assert_eq!(responder.to_string(), "Responder { function: ARTIKEL, method: GET }");
+
+        let response = responder.responder.respond(&request("ARTIKEL.GET"));
+        assert_eq!(response.comresult.status, 200);
+        assert_eq!(response.body.0, Some(json!({ "FUNCTION": "ARTIKEL.GET" })));
+    }
+}
@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use figment::{
+    providers::{Format, Toml},
+    Figment,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    journal::{Journal, ReceivedRequest},
+    mock_registry::{self, MockEntry, MockRegistry},
+    AppState, MockResource,
+};
+
+/// The reasons why a call to the admin API can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminError {
+    /// There is no mock resource with the given ID.
+    NotFound(usize),
+    /// The request body isn't a valid mock resource.
+    InvalidBody(String),
+    /// The stateful functions could not be reseeded.
+    ResetFailed(String),
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            AdminError::NotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("No mock resource with ID {}", id),
+            ),
+            AdminError::InvalidBody(err) => (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid mock resource: {}", err),
+            ),
+            AdminError::ResetFailed(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to reset: {}", err),
+            ),
+        };
+        (status, Json(json!({ "error": error }))).into_response()
+    }
+}
+
+/// Parses a mock resource from the request body and checks it like the server does on startup.
+fn parse_resource(state: &AppState, headers: &HeaderMap, body: &str) -> Result<MockResource, AdminError> {
+    let resource = deserialize_resource(headers, body)?;
+    state
+        .validate_resource(&resource)
+        .map_err(|err| AdminError::InvalidBody(format!("{:#}", err)))?;
+    Ok(resource)
+}
+
+/// Deserializes a mock resource from the request body, which is TOML if the content type says so and JSON otherwise.
+fn deserialize_resource(headers: &HeaderMap, body: &str) -> Result<MockResource, AdminError> {
+    let is_toml = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("toml"));
+
+    if is_toml {
+        Figment::from(Toml::string(body))
+            .extract()
+            .map_err(|err| AdminError::InvalidBody(err.to_string()))
+    } else {
+        serde_json::from_str(body).map_err(|err| AdminError::InvalidBody(err.to_string()))
+    }
+}
+
+pub async fn list_mocks(State(mocks): State<Arc<MockRegistry>>) -> Json<Vec<MockEntry>> {
+    Json(mocks.list())
+}
+
+pub async fn add_mock(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<(StatusCode, Json<MockEntry>), AdminError> {
+    let resource = parse_resource(&state, &headers, &body)?;
+    Ok((StatusCode::CREATED, Json(state.mocks.add(resource))))
+}
+
+pub async fn clear_mocks(State(mocks): State<Arc<MockRegistry>>) -> StatusCode {
+    mocks.clear();
+    StatusCode::NO_CONTENT
+}
+
+pub async fn get_mock(
+    State(mocks): State<Arc<MockRegistry>>,
+    Path(id): Path<usize>,
+) -> Result<Json<MockEntry>, AdminError> {
+    mocks.get(id).map(Json).ok_or(AdminError::NotFound(id))
+}
+
+pub async fn replace_mock(
+    State(state): State<AppState>,
+    Path(id): Path<usize>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<MockEntry>, AdminError> {
+    let resource = parse_resource(&state, &headers, &body)?;
+    let entry = state.mocks.replace(id, resource).ok_or(AdminError::NotFound(id))?;
+    // the new resource starts over, e.g. at the first response of its sequence
+    state.counters.forget(&mock_registry::counter_key(id));
+    Ok(Json(entry))
+}
+
+pub async fn remove_mock(
+    State(mocks): State<Arc<MockRegistry>>,
+    Path(id): Path<usize>,
+) -> Result<Json<MockEntry>, AdminError> {
+    mocks.remove(id).map(Json).ok_or(AdminError::NotFound(id))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RequestsQuery {
+    /// Only return EXECJSON requests for this function, e.g. `ARTIKEL.PUT`.
+    function: Option<String>,
+}
+
+pub async fn list_requests(
+    State(journal): State<Arc<Journal>>,
+    Query(query): Query<RequestsQuery>,
+) -> Json<Vec<ReceivedRequest>> {
+    let requests = journal
+        .requests()
+        .into_iter()
+        .filter(|request| match &query.function {
+            Some(function) => request
+                .function
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(function)),
+            None => true,
+        })
+        .collect();
+    Json(requests)
+}
+
+pub async fn clear_requests(State(journal): State<Arc<Journal>>) -> StatusCode {
+    journal.clear();
+    StatusCode::NO_CONTENT
+}
+
+/// Restores the mock resources from the configuration, resets all call counters, closes all cursors, reseeds the
+/// stateful functions and the fault injection, rewinds the cassette, clears the state of the scripts and the journal.
+/// Sessions are kept, so registered clients can carry on.
+pub async fn reset(State(state): State<AppState>) -> Result<StatusCode, AdminError> {
+    state
+        .store
+        .reset()
+        .map_err(|err| AdminError::ResetFailed(err.to_string()))?;
+    state.mocks.reset();
+    state.counters.reset();
+    state.cursors.reset();
+    state.faults.reset();
+    #[cfg(feature = "scripting")]
+    state.scripts.reset();
+    state.journal.clear();
+    if let Some(cassette) = &state.cassette {
+        cassette.reset();
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, HeaderMap, HeaderValue};
+    use pretty_assertions::assert_eq;
+
+    use crate::{MockResourceMethod, MockResourceRevision};
+
+    use super::{deserialize_resource, AdminError};
+
+    #[test]
+    fn parse_json_and_toml() {
+        let resource = deserialize_resource(
+            &HeaderMap::new(),
+            r#"{"function": "ARTIKEL", "method": "GET", "revision": "2-3", "data_source": {"type": "String", "value": "{}"}}"#,
+        )
+        .unwrap();
+        assert_eq!(resource.function, "ARTIKEL");
+        assert_eq!(resource.revision, MockResourceRevision::Range { min: 2, max: 3 });
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/toml"));
+        let resource = deserialize_resource(
+            &headers,
+            r#"
+            function = "ARTIKEL"
+            method = "INSERT"
+            revision = 1
+            parameters = { ARTNR = "^A" }"#,
+        )
+        .unwrap();
+        assert_eq!(resource.method, MockResourceMethod::Insert);
+        assert_eq!(resource.parameters.unwrap()["ARTNR"].to_string(), "/^A/");
+
+        assert_eq!(
+            matches!(deserialize_resource(&headers, "function = 1"), Err(AdminError::InvalidBody(_))),
+            true
+        );
+    }
+
+    #[test]
+    fn serialized_entry_roundtrips() {
+        let resource = deserialize_resource(
+            &HeaderMap::new(),
+            r#"{"function": "ARTIKEL", "method": "GET", "revision": 3}"#,
+        )
+        .unwrap();
+        let entry = crate::mock_registry::MockEntry { id: 1, resource };
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["id"], 1);
+        assert_eq!(value["revision"], 3);
+        assert_eq!(value["method"], "GET");
+
+        let resource = deserialize_resource(&HeaderMap::new(), &value.to_string()).unwrap();
+        assert_eq!(resource.to_string(), entry.resource.to_string());
+    }
+}
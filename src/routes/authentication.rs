@@ -0,0 +1,127 @@
+use axum::http::{HeaderMap, StatusCode};
+use encoding_rs::WINDOWS_1252;
+
+use crate::sessions::SessionRegistry;
+
+use super::{ComResult, ComResultBuilder, WebserviceRequest};
+
+/// The reasons why a request can fail authentication.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuthenticationError {
+    ServicePassNotKnown,
+    MandatoryHeaderMissing,
+    ExecuteModeNotKnown,
+    HashNotCorrect,
+}
+
+impl AuthenticationError {
+    fn reason(&self) -> &'static str {
+        match self {
+            AuthenticationError::ServicePassNotKnown => "wwsvc-mock: ServicePass not known",
+            AuthenticationError::MandatoryHeaderMissing => "wwsvc-mock: Mandatory header missing",
+            AuthenticationError::ExecuteModeNotKnown => "wwsvc-mock: Execute mode not known",
+            AuthenticationError::HashNotCorrect => "wwsvc-mock: Hash not correct",
+        }
+    }
+
+    /// The COMRESULT the WEBWARE returns when deregistering fails. The WEBWARE doesn't tell
+    /// the client why, so this is always a 404.
+    pub fn deregister_comresult(&self) -> ComResult {
+        ComResultBuilder::with_status(StatusCode::NOT_FOUND)
+            .code("404 Resource not found")
+            .info("ERROR ServicePass not known")
+            .info2(self.reason())
+            .build()
+            .unwrap()
+    }
+
+    /// The COMRESULT the WEBWARE returns when an EXECJSON call fails authentication.
+    pub fn exec_comresult(&self) -> ComResult {
+        match self {
+            AuthenticationError::ServicePassNotKnown => self.deregister_comresult(),
+            _ => ComResultBuilder::with_status(StatusCode::UNAUTHORIZED)
+                .code("401 Unauthorized")
+                .info("ERROR Authorization failed")
+                .info2(self.reason())
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, AuthenticationError> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(AuthenticationError::MandatoryHeaderMissing)
+}
+
+/// Checks the `WWSVC-*` headers of a request, including the `WWSVC-HASH`, which is the MD5 hash
+/// of the application ID and the `WWSVC-TS` header, encoded in Windows-1252.
+pub fn validate_headers(headers: &HeaderMap, application_id: &str) -> Result<(), AuthenticationError> {
+    let execute_mode = header(headers, "WWSVC-EXECUTE-MODE")?;
+    header(headers, "WWSVC-REQID")?;
+    let ts = header(headers, "WWSVC-TS")?;
+    let hash = header(headers, "WWSVC-HASH")?;
+
+    if !["SYNCHRON", "ASYNCHRON"].contains(&execute_mode) {
+        return Err(AuthenticationError::ExecuteModeNotKnown);
+    }
+
+    let expected_pre_hash = format!("{}{}", application_id, ts);
+    let (cow, _, _) = WINDOWS_1252.encode(expected_pre_hash.as_str());
+    let expected_hash = format!("{:x}", md5::compute(cow));
+
+    if hash != expected_hash {
+        return Err(AuthenticationError::HashNotCorrect);
+    }
+
+    Ok(())
+}
+
+/// Authenticates an EXECJSON request by checking its `WWSVC_PASSINFO` block and its headers
+/// against the issued service passes.
+pub fn authenticate_request(
+    request: &WebserviceRequest,
+    headers: &HeaderMap,
+    sessions: &SessionRegistry,
+) -> Result<(), AuthenticationError> {
+    let Some(session) = sessions.lookup(&request.pass_info.service_pass) else {
+        return Err(AuthenticationError::ServicePassNotKnown);
+    };
+
+    if !["SYNCHRON", "ASYNCHRON"].contains(&request.pass_info.execute_mode.as_str()) {
+        return Err(AuthenticationError::ExecuteModeNotKnown);
+    }
+
+    validate_headers(headers, &session.application_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderMap;
+
+    use super::AuthenticationError;
+
+    fn headers(execute_mode: &str, hash: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("WWSVC-EXECUTE-MODE", execute_mode.parse().unwrap());
+        headers.insert("WWSVC-REQID", "1".parse().unwrap());
+        headers.insert("WWSVC-TS", "Mon, 01 Jan 2000 00:00:00 GMT".parse().unwrap());
+        headers.insert("WWSVC-HASH", hash.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn valid_headers() {
+        let hash = format!("{:x}", md5::compute("appidMon, 01 Jan 2000 00:00:00 GMT"));
+        assert_eq!(super::validate_headers(&headers("SYNCHRON", &hash), "appid"), Ok(()));
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert_eq!(super::validate_headers(&HeaderMap::new(), "appid"), Err(AuthenticationError::MandatoryHeaderMissing));
+        assert_eq!(super::validate_headers(&headers("UNKNOWN", "a"), "appid"), Err(AuthenticationError::ExecuteModeNotKnown));
+        assert_eq!(super::validate_headers(&headers("SYNCHRON", "a"), "appid"), Err(AuthenticationError::HashNotCorrect));
+    }
+}
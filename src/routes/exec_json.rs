@@ -1,49 +1,411 @@
-use std::sync::Arc;
+use axum::{
+    extract::State,
+    http::{HeaderMap, Method, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
 
-use axum::{extract::State, http::StatusCode, Json};
+use serde_json::{json, Value};
 
-use crate::{AppConfig, OptionalJson};
+use crate::{
+    cursors::{CursorRegistry, CURSOR_HEADER, MAX_LINES_HEADER},
+    dataset::{self, DatasetError},
+    diagnostics::{near_misses, NearMiss},
+    faults::sent_status,
+    journal::{ReceivedRequest, RequestRoute},
+    mock_registry,
+    template::{render, TemplateContext},
+    AppConfig, AppState, DatasetConfig, FileOrString, MockResource, MockResourceMethod, OptionalJson,
+};
 
-use super::{ComResultBuilder, ServiceResponse, WebserviceRequest};
+use super::{
+    apply_latency,
+    authentication::{authenticate_request, AuthenticationError},
+    ComResult, ComResultBuilder, LookupError, ServiceResponse, WebserviceRequest,
+};
 
-pub async fn exec_json(
-    State(app_config): State<Arc<AppConfig>>,
-    Json(request): Json<WebserviceRequest>,
-) -> ServiceResponse<OptionalJson> {
-    let resource = match request.lookup_resource(&app_config.mock_resources) {
-        Some(resource) => resource,
-        None => {
-            let comresult = ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
-                .bereich("WWSVC")
-                .code("400 Bad Request")
-                .info("Es wurde eine fehlerhafte Anforderung übergeben.")
-                .info2("Funktionsname nicht bekannt.")
-                .info3(&request.function.function_name)
-                .errno("20")
-                .errnotxt("SVCERR_UNKNOWN_FUNCTION (20)")
-                .build()
-                .unwrap();
-            return ServiceResponse::<OptionalJson> {
-                comresult,
+/// How the journal names the upstream WEBWARE when it answered a request in the proxy mode.
+#[cfg(feature = "proxy")]
+const UPSTREAM: &str = "upstream WEBWARE";
+
+/// The outcome of an EXECJSON call, before the latency is applied.
+struct Outcome {
+    response: ServiceResponse<OptionalJson>,
+    /// The matched mock resource, along with the number of previous calls to it.
+    resource: Option<(MockResource, usize)>,
+    /// The responder that answered the request, if any.
+    responder: Option<String>,
+    /// The mock resources that came closest to matching, if nothing answered the request.
+    near_misses: Option<Vec<NearMiss>>,
+    /// The cursor to send back in the `WWSVC-CURSOR` header, if the request paged with a cursor.
+    cursor: Option<String>,
+}
+
+impl Outcome {
+    fn unmatched(comresult: ComResult, body: OptionalJson) -> Self {
+        Outcome {
+            response: ServiceResponse { comresult, body },
+            resource: None,
+            responder: None,
+            near_misses: None,
+            cursor: None,
+        }
+    }
+
+    /// An outcome for a request that no mock resource matched, explaining why.
+    fn missed(
+        app_config: &AppConfig,
+        request: &WebserviceRequest,
+        err: LookupError,
+        resources: &[MockResource],
+    ) -> Self {
+        let near_misses = near_misses(request, resources);
+        tracing::warn!(
+            "No mock resource matches {} (Revision {})",
+            request.function.function_name,
+            request.function.revision
+        );
+        for near_miss in &near_misses {
+            tracing::warn!("  {}: {}", near_miss.resource, near_miss.mismatches.join(", "));
+        }
+
+        let body = match app_config.diagnostics {
+            true => OptionalJson(Some(json!({
+                "WWSVC_MOCK_DIAGNOSTICS": {
+                    "NEAR_MISSES": near_misses,
+                }
+            }))),
+            false => OptionalJson(None),
+        };
+
+        Outcome {
+            response: ServiceResponse {
+                comresult: err.comresult(request),
+                body,
+            },
+            resource: None,
+            responder: None,
+            near_misses: Some(near_misses),
+            cursor: None,
+        }
+    }
+}
+
+fn execute(state: &AppState, headers: &HeaderMap, request: &WebserviceRequest) -> Outcome {
+    let AppState {
+        config: app_config,
+        sessions,
+        store,
+        counters,
+        cursors,
+        mocks,
+        cassette,
+        #[cfg(feature = "scripting")]
+        scripts,
+        schemas,
+        ..
+    } = state;
+
+    if app_config.webware.authenticate_requests {
+        if let Err(err) = authenticate_request(request, headers, sessions) {
+            return Outcome::unmatched(err.exec_comresult(), OptionalJson(None));
+        }
+    } else if app_config.webware.sessions.enabled && sessions.lookup(&request.pass_info.service_pass).is_none() {
+        let err = AuthenticationError::ServicePassNotKnown;
+        return Outcome::unmatched(err.exec_comresult(), OptionalJson(None));
+    }
+
+    if let Err(err) = schemas.validate_request(request) {
+        tracing::warn!("{} doesn't match the schema: {:?}", request.function.function_name, err);
+        return Outcome::unmatched(err.comresult(), OptionalJson(None));
+    }
+
+    match cassette.as_ref().and_then(|cassette| cassette.replay(request)) {
+        Some(Ok(interaction)) => {
+            let comresult = match &interaction.comresult {
+                Some(comresult) => ComResult::from(comresult),
+                None => ComResult::ok(),
+            };
+            return Outcome::unmatched(comresult, interaction.response.as_json_value());
+        }
+        Some(Err(deviation)) => {
+            tracing::error!("Request deviates from the cassette. {}", deviation);
+            return Outcome::unmatched(deviation.comresult(), OptionalJson(None));
+        }
+        None => {}
+    }
+
+    if let Some(responder) = app_config.responders.iter().find(|responder| responder.matches(request)) {
+        return Outcome {
+            response: responder.responder.respond(request),
+            resource: None,
+            responder: Some(responder.to_string()),
+            near_misses: None,
+            cursor: None,
+        };
+    }
+
+    let (global_ids, global_resources) = mocks.snapshot();
+    let application = sessions
+        .lookup(&request.pass_info.service_pass)
+        .and_then(|session| sessions.application(&session.application_hash))
+        .filter(|application| application.mock_resources.is_some());
+    let resources = application
+        .and_then(|application| application.mock_resources.as_deref())
+        .unwrap_or(&global_resources);
+
+    let (index, resource) = match request.lookup_resource(resources) {
+        Ok(found) => found,
+        // stateful functions answer every revision, so a resource for another revision doesn't hide them
+        Err(err) => {
+            let result = request
+                .function_and_method()
+                .and_then(|(function, method)| {
+                    store
+                        .execute(function, &method, &request.function.parameter)
+                        .map(|result| (method, result))
+                });
+            return match result {
+                Some((MockResourceMethod::Get, Ok(Some(body)))) => {
+                    match process_list(&DatasetConfig::default(), cursors, headers, request, body) {
+                        Ok((body, cursor)) => Outcome {
+                            cursor,
+                            ..Outcome::unmatched(ComResult::ok(), OptionalJson(Some(body)))
+                        },
+                        Err(err) => Outcome::unmatched(err.comresult(), OptionalJson(None)),
+                    }
+                }
+                Some((_, Ok(body))) => Outcome::unmatched(ComResult::ok(), OptionalJson(body)),
+                Some((_, Err(err))) => Outcome::unmatched(err.comresult(), OptionalJson(None)),
+                None => Outcome::missed(app_config, request, err, resources),
+            };
+        }
+    };
+
+    // resources are counted by their position, so equal resources with different data don't share a counter
+    let counter = match application {
+        Some(application) => format!("{} {}", application.webservices.application_hash, index),
+        None => mock_registry::counter_key(global_ids[index]),
+    };
+    let call = counters.next(&counter);
+
+    let mut comresult = match &resource.comresult {
+        Some(comresult) => ComResult::from(comresult),
+        None => ComResult::ok(),
+    };
+
+    let data_source = resource.data_source_for_call(call);
+    let body = match (resource.template, data_source) {
+        #[cfg(feature = "scripting")]
+        (_, FileOrString::Script { .. }) => match scripts.run(data_source, request, call) {
+            Ok(output) => {
+                if let Some(script_comresult) = &output.comresult {
+                    comresult = ComResult::from(script_comresult);
+                }
+                OptionalJson(output.body)
+            }
+            Err(err) => {
+                tracing::error!("Script of {} failed: {:#}", resource, err);
+                comresult = ComResultBuilder::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .bereich("WWSVC")
+                    .code("500 Internal Server Error")
+                    .info("Bei der Verarbeitung ist ein Fehler aufgetreten.")
+                    .info2("wwsvc-mock: Script failed")
+                    .info3(&format!("{:#}", err))
+                    .build()
+                    .unwrap();
+                OptionalJson(None)
+            }
+        },
+        (true, FileOrString::File { .. } | FileOrString::String { .. }) => {
+            let rendered = render(&data_source.as_string(), &TemplateContext::new(request, call));
+            match serde_json::from_str(&rendered) {
+                Ok(body) => OptionalJson(Some(body)),
+                Err(err) => {
+                    tracing::error!("Rendered template of {} isn't valid JSON: {}", resource, err);
+                    comresult = ComResultBuilder::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .bereich("WWSVC")
+                        .code("500 Internal Server Error")
+                        .info("Bei der Verarbeitung ist ein Fehler aufgetreten.")
+                        .info2("wwsvc-mock: Template failed")
+                        .info3(&format!("Rendered template isn't valid JSON: {}", err))
+                        .build()
+                        .unwrap();
+                    OptionalJson(None)
+                }
+            }
+        }
+        _ => data_source.as_json_value(),
+    };
+
+    let mut cursor = None;
+    let body = match (resource.effective_dataset(), body) {
+        (Some(dataset), OptionalJson(Some(body))) if resource.method == MockResourceMethod::Get => {
+            match process_list(&dataset, cursors, headers, request, body) {
+                Ok((body, next_cursor)) => {
+                    cursor = next_cursor;
+                    OptionalJson(Some(body))
+                }
+                Err(err) => {
+                    comresult = err.comresult();
+                    OptionalJson(None)
+                }
+            }
+        }
+        (_, body) => body,
+    };
+
+    Outcome {
+        response: ServiceResponse::<OptionalJson> { comresult, body },
+        resource: Some((resource, call)),
+        responder: None,
+        near_misses: None,
+        cursor,
+    }
+}
+
+/// Processes a list response with the dataset and pages it with the cursor sent with the request, if any. Returns the
+/// cursor to send back. A cursor takes precedence over the paging parameters, which would page the cursor's pages again.
+fn process_list(
+    dataset: &DatasetConfig,
+    cursors: &CursorRegistry,
+    headers: &HeaderMap,
+    request: &WebserviceRequest,
+    body: Value,
+) -> Result<(Value, Option<String>), DatasetError> {
+    let Some((cursor, position)) = cursors.requested(headers, request)? else {
+        return Ok((dataset::apply(dataset, request, body)?, None));
+    };
+    let mut body = dataset::select(dataset, request, body)?;
+
+    let max_lines = headers
+        .get(MAX_LINES_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<usize>().ok());
+    let more = dataset::paginate(&mut body, position, max_lines);
+    let next = position + max_lines.unwrap_or_default();
+    Ok((body, Some(cursors.advance(&cursor, request, next, more))))
+}
+
+/// Forwards an unmatched request to the upstream WEBWARE and records the exchange. Once the upstream WEBWARE answered
+/// it, the request no longer counts as unmatched.
+#[cfg(feature = "proxy")]
+async fn forward(
+    state: &AppState,
+    proxy: &crate::proxy::Proxy,
+    method: &Method,
+    request: &WebserviceRequest,
+    outcome: &mut Outcome,
+) {
+    let response = match proxy.forward(method, request).await {
+        Ok(response) => response,
+        Err(err) => {
+            tracing::error!(
+                "Failed to forward {} to the upstream WEBWARE: {:#}",
+                request.function.function_name,
+                err
+            );
+            outcome.response = ServiceResponse {
+                comresult: ComResultBuilder::with_status(StatusCode::BAD_GATEWAY)
+                    .bereich("WWSVC")
+                    .code("502 Bad Gateway")
+                    .info("Der Upstream-WEBWARE hat nicht korrekt geantwortet.")
+                    .info2("wwsvc-mock: Upstream request failed")
+                    .build()
+                    .unwrap(),
                 body: OptionalJson(None),
             };
+            return;
         }
     };
 
-    let json = resource.data_source.as_json_value();
-    let comresult = ComResultBuilder::with_status(StatusCode::OK)
-        .bereich("WWSVC")
-        .code("200 OK")
-        .errno("0")
-        .errnotxt("SVCERR_NO_ERROR (0)")
-        .info("Kein Fehler")
-        .info2("")
-        .info3("")
-        .build()
-        .unwrap();
+    match proxy.record(request, &response) {
+        Ok(Some(resource)) => match state.validate_resource(&resource) {
+            Ok(()) => {
+                tracing::info!("Recorded {}", resource);
+                state.mocks.add(resource);
+            }
+            Err(err) => tracing::error!("Recorded {}, but didn't add it: {:#}", resource, err),
+        },
+        Ok(None) => {}
+        Err(err) => tracing::error!(
+            "Failed to record {}: {:#}",
+            request.function.function_name,
+            err
+        ),
+    }
 
-    ServiceResponse::<OptionalJson> {
-        comresult,
-        body: json,
+    outcome.response = ServiceResponse {
+        comresult: response.comresult,
+        body: OptionalJson(response.body),
+    };
+    outcome.responder = Some(UPSTREAM.to_string());
+    outcome.near_misses = None;
+}
+
+// the method and the outcome are only needed to forward unmatched requests
+#[cfg_attr(not(feature = "proxy"), allow(unused_variables, unused_mut))]
+pub async fn exec_json(
+    State(state): State<AppState>,
+    method: Method,
+    headers: HeaderMap,
+    Json(request): Json<WebserviceRequest>,
+) -> Response {
+    let mut outcome = execute(&state, &headers, &request);
+    let AppState {
+        config: app_config,
+        faults,
+        journal,
+        ..
+    } = &state;
+
+    #[cfg(feature = "proxy")]
+    if let (Some(proxy), Some(_)) = (&state.proxy, &outcome.near_misses) {
+        forward(&state, proxy, &method, &request, &mut outcome).await;
     }
+
+    let fault = match &outcome.resource {
+        Some((resource, call)) => {
+            let latency = resource.latency.as_ref().or(app_config.latency.as_ref());
+            apply_latency(latency, *call == 0).await;
+            faults.pick(resource.faults.as_ref().or(app_config.faults.as_ref()))
+        }
+        None => {
+            apply_latency(app_config.latency.as_ref(), false).await;
+            faults.pick(app_config.faults.as_ref())
+        }
+    };
+
+    let mut received = ReceivedRequest::new(RequestRoute::ExecJson, &headers);
+    received.function = Some(request.function.function_name.clone());
+    received.revision = Some(request.function.revision);
+    received.parameters = request
+        .function
+        .parameter
+        .iter()
+        .map(|parameter| (parameter.name.clone(), parameter.value.clone()))
+        .collect();
+    received.service_pass = Some(request.pass_info.service_pass.clone());
+    received.matched_resource = outcome
+        .resource
+        .as_ref()
+        .map(|(resource, _)| resource.to_string())
+        .or(outcome.responder);
+    received.near_misses = outcome.near_misses;
+
+    let response = match fault {
+        Some(fault) => fault.respond(outcome.response),
+        None => {
+            let mut response = outcome.response.into_response();
+            if let Some(cursor) = outcome.cursor.and_then(|cursor| cursor.parse().ok()) {
+                response.headers_mut().insert(CURSOR_HEADER, cursor);
+            }
+            response
+        }
+    };
+
+    received.status = sent_status(&response);
+    journal.record(received);
+
+    response
 }
@@ -3,32 +3,75 @@ use std::str::FromStr;
 use axum::{http::StatusCode, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 
-use crate::app_config::{MockResource, MockResourceMethod};
+use crate::{
+    app_config::{ComResultConfig, LatencyConfig, MockResource, MockResourceMethod},
+    OptionalJson,
+};
 
+pub mod admin;
+pub mod authentication;
 pub mod exec_json;
 pub mod service_pass;
 
+/// Waits for the delay of the given latency configuration, if any.
+pub async fn apply_latency(latency: Option<&LatencyConfig>, first_call: bool) {
+    if let Some(latency) = latency {
+        let delay = latency.delay(first_call);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// The `COMRESULT` block the WEBWARE sends with every response.
+///
+/// The `STATUS` is also used as the HTTP status code of the response.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ComResult {
+    /// The `STATUS`, e.g. `200`.
     #[serde(rename = "STATUS")]
     pub status: u32,
+    /// The `CODE`, e.g. `200 OK`.
     #[serde(rename = "CODE")]
     pub code: String,
+    /// The `INFO`.
     #[serde(rename = "INFO")]
     pub info: String,
+    /// The `INFO2`.
     #[serde(rename = "INFO2")]
     pub info2: Option<String>,
+    /// The `INFO3`.
     #[serde(rename = "INFO3")]
     pub info3: Option<String>,
+    /// The `ERRNO`.
     #[serde(rename = "ERRNO")]
     pub errno: Option<String>,
+    /// The `BEREICH`, usually `WWSVC`.
     #[serde(rename = "BEREICH")]
     pub bereich: Option<String>,
+    /// The `ERRNOTXT`, e.g. `SVCERR_NO_ERROR (0)`.
     #[serde(rename = "ERRNOTXT")]
     pub errnotxt: Option<String>,
 }
 
-#[derive(Default)]
+impl ComResult {
+    /// Returns the COMRESULT of a successful call.
+    pub fn ok() -> Self {
+        ComResultBuilder::with_status(StatusCode::OK)
+            .bereich("WWSVC")
+            .code("200 OK")
+            .errno("0")
+            .errnotxt("SVCERR_NO_ERROR (0)")
+            .info("Kein Fehler")
+            .info2("")
+            .info3("")
+            .build()
+            .unwrap()
+    }
+}
+
+/// A builder for a [ComResult]. The status, code and info are required.
+#[derive(Default, Debug)]
 pub struct ComResultBuilder {
     status: Option<u32>,
     code: Option<String>,
@@ -41,54 +84,65 @@ pub struct ComResultBuilder {
 }
 
 impl ComResultBuilder {
+    /// Creates an empty builder.
     pub fn new() -> Self {
         ComResultBuilder::default()
     }
 
+    /// Creates a builder with the given status.
     pub fn with_status(status: StatusCode) -> Self {
         ComResultBuilder::new().status(status.as_u16() as u32)
     }
 
+    /// Sets the `STATUS`.
     pub fn status(mut self, status: u32) -> Self {
         self.status = Some(status);
         self
     }
 
+    /// Sets the `CODE`.
     pub fn code(mut self, code: &str) -> Self {
         self.code = Some(code.to_string());
         self
     }
 
+    /// Sets the `INFO`.
     pub fn info(mut self, info: &str) -> Self {
         self.info = Some(info.to_string());
         self
     }
 
+    /// Sets the `INFO2`.
     pub fn info2(mut self, info2: &str) -> Self {
         self.info2 = Some(info2.to_string());
         self
     }
 
+    /// Sets the `INFO3`.
     pub fn info3(mut self, info3: &str) -> Self {
         self.info3 = Some(info3.to_string());
         self
     }
 
+    /// Sets the `ERRNO`.
     pub fn errno(mut self, errno: &str) -> Self {
         self.errno = Some(errno.to_string());
         self
     }
 
+    /// Sets the `BEREICH`.
     pub fn bereich(mut self, bereich: &str) -> Self {
         self.bereich = Some(bereich.to_string());
         self
     }
 
+    /// Sets the `ERRNOTXT`.
     pub fn errnotxt(mut self, errnotxt: &str) -> Self {
         self.errnotxt = Some(errnotxt.to_string());
         self
     }
 
+    /// Builds the COMRESULT, failing if the status, code or info is missing.
     pub fn build(self) -> anyhow::Result<ComResult> {
         Ok(ComResult {
             status: self.status.ok_or(anyhow::anyhow!("status is required"))?,
@@ -103,14 +157,75 @@ impl ComResultBuilder {
     }
 }
 
+impl From<&ComResultConfig> for ComResult {
+    fn from(config: &ComResultConfig) -> Self {
+        let mut builder = ComResultBuilder::new()
+            .status(config.status)
+            .code(&config.code())
+            .info(&config.info);
+        if let Some(info2) = &config.info2 {
+            builder = builder.info2(info2);
+        }
+        if let Some(info3) = &config.info3 {
+            builder = builder.info3(info3);
+        }
+        if let Some(errno) = &config.errno {
+            builder = builder.errno(errno);
+        }
+        if let Some(errnotxt) = &config.errnotxt {
+            builder = builder.errnotxt(errnotxt);
+        }
+        if let Some(bereich) = &config.bereich {
+            builder = builder.bereich(bereich);
+        }
+        builder.build().unwrap()
+    }
+}
+
+impl From<&ComResult> for ComResultConfig {
+    fn from(comresult: &ComResult) -> Self {
+        ComResultConfig {
+            status: comresult.status,
+            code: Some(comresult.code.clone()),
+            info: comresult.info.clone(),
+            info2: comresult.info2.clone(),
+            info3: comresult.info3.clone(),
+            errno: comresult.errno.clone(),
+            errnotxt: comresult.errnotxt.clone(),
+            bereich: comresult.bereich.clone(),
+        }
+    }
+}
+
+/// A response of the WEBWARE, consisting of the [ComResult] and the body next to it.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ServiceResponse<T> {
+    /// The `COMRESULT` of the response.
     #[serde(rename = "COMRESULT")]
     pub comresult: ComResult,
+    /// The body, flattened next to the `COMRESULT`.
     #[serde(flatten)]
     pub body: T,
 }
 
+impl ServiceResponse<OptionalJson> {
+    /// Creates a successful response with the given body, e.g. `json!({ "ARTIKEL": { "ARTNR": "A1" } })`.
+    pub fn ok(body: serde_json::Value) -> Self {
+        ServiceResponse {
+            comresult: ComResult::ok(),
+            body: OptionalJson(Some(body)),
+        }
+    }
+
+    /// Creates a response without a body, e.g. to respond with an error.
+    pub fn with_comresult(comresult: ComResult) -> Self {
+        ServiceResponse {
+            comresult,
+            body: OptionalJson(None),
+        }
+    }
+}
+
 impl<T: Serialize> IntoResponse for ServiceResponse<T> {
     fn into_response(self) -> axum::response::Response {
         // appeasing the coverage gods
@@ -120,73 +235,202 @@ impl<T: Serialize> IntoResponse for ServiceResponse<T> {
     }
 }
 
+/// A parameter of an EXECJSON request.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WebserviceParameter {
+    /// The `PNAME` of the parameter, e.g. `ARTNR`.
     #[serde(rename = "PNAME")]
     pub name: String,
+    /// The `PCONTENT` of the parameter.
     #[serde(rename = "PCONTENT")]
     pub value: String,
 }
 
+/// The `WWSVC_FUNCTION` block of an EXECJSON request.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WebserviceFunction {
+    /// The function name including the method, e.g. `ARTIKEL.GET`.
     #[serde(rename = "FUNCTIONNAME")]
     pub function_name: String,
+    /// The requested revision of the function.
     #[serde(rename = "REVISION")]
     pub revision: u32,
+    /// The parameters of the call.
     #[serde(rename = "PARAMETER")]
     pub parameter: Vec<WebserviceParameter>,
 }
 
+/// The `WWSVC_PASSINFO` block of an EXECJSON request.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WebservicePassInfo {
+    /// The service pass the client got on registration.
     #[serde(rename = "SERVICEPASS")]
     pub service_pass: String,
+    /// The application hash of the client.
     #[serde(rename = "APPHASH")]
     pub app_hash: String,
+    /// The timestamp of the request.
     #[serde(rename = "TIMESTAMP")]
     pub timestamp: String,
+    /// The ID of the request.
     #[serde(rename = "REQUESTID")]
     pub request_id: usize,
+    /// The execute mode, usually `SYNCHRON`.
     #[serde(rename = "EXECUTE_MODE")]
     pub execute_mode: String,
 }
 
+/// An EXECJSON request as sent by the client.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WebserviceRequest {
+    /// The called function and its parameters.
     #[serde(rename = "WWSVC_FUNCTION")]
     pub function: WebserviceFunction,
+    /// The service pass and application of the client.
     #[serde(rename = "WWSVC_PASSINFO")]
     pub pass_info: WebservicePassInfo,
 }
 
 impl WebserviceRequest {
-    pub fn lookup_resource(&self, resources: &[MockResource]) -> Option<MockResource> {
-        resources
+    /// Returns the value of the parameter with the given name, if the request contains it.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.function
+            .parameter
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .map(|parameter| parameter.value.as_str())
+    }
+
+    /// Splits the function name of the request into the function and the method, e.g.
+    /// `ARTIKEL.GET` into `ARTIKEL` and [MockResourceMethod::Get].
+    pub fn function_and_method(&self) -> Option<(&str, MockResourceMethod)> {
+        let (function_name, method) = self.function.function_name.split_once('.')?;
+        let method = MockResourceMethod::from_str(method).ok()?;
+        Some((function_name, method))
+    }
+
+    /// Returns all values sent for the parameter with the given name.
+    pub(crate) fn parameter_values(&self, name: &str) -> Vec<&str> {
+        self.function
+            .parameter
+            .iter()
+            .filter(|parameter| parameter.name == name)
+            .map(|parameter| parameter.value.as_str())
+            .collect()
+    }
+
+    fn matches_parameters(&self, resource: &MockResource) -> bool {
+        match resource.parameters {
+            Some(ref parameters) => {
+                parameters
+                    .iter()
+                    .all(|(name, matcher)| matcher.matches(&self.parameter_values(name)))
+                    && (!resource.strict_parameters
+                        || self.function.parameter.iter().all(|parameter| {
+                            parameters.contains_key(&parameter.name)
+                                || resource.handles_parameter(&parameter.name)
+                        }))
+            }
+            None => self
+                .function
+                .parameter
+                .iter()
+                .all(|parameter| resource.handles_parameter(&parameter.name)),
+        }
+    }
+
+    /// Finds the first resource that matches the request, returning its index in `resources` along with a copy of it.
+    pub(crate) fn lookup_resource(&self, resources: &[MockResource]) -> Result<(usize, MockResource), LookupError> {
+        let Some((function_name, method)) = self.function_and_method() else {
+            return Err(LookupError::UnknownFunction);
+        };
+
+        let mut candidates = resources
             .iter()
-            .find(|resource| {
-                let split = self
-                    .function
-                    .function_name
-                    .split('.')
-                    .collect::<Vec<&str>>();
-                if split.len() != 2 {
-                    return false;
-                }
-                let function_name = split[0];
-                let method = MockResourceMethod::from_str(split[1]).unwrap();
+            .enumerate()
+            .filter(|(_, resource)| {
                 resource.function == function_name
                     && resource.method == method
-                    && match resource.parameters {
-                        Some(ref parameters) => parameters.iter().all(|(k, v)| {
-                            self.function.parameter.iter().any(|request_parameter| {
-                                request_parameter.name == *k && v.is_match(&request_parameter.value)
-                            })
-                        }),
-                        None => self.function.parameter.is_empty(),
-                    }
+                    && self.matches_parameters(resource)
             })
-            .cloned()
+            .peekable();
+
+        if candidates.peek().is_none() {
+            return Err(LookupError::UnknownFunction);
+        }
+
+        candidates
+            .find(|(_, resource)| resource.revision.matches(self.function.revision))
+            .map(|(index, resource)| (index, resource.clone()))
+            .ok_or(LookupError::RevisionNotSupported)
+    }
+}
+
+/// The reason why no mock resource could be found for a request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LookupError {
+    /// No resource matches the function, method and parameters of the request.
+    UnknownFunction,
+    /// At least one resource matches the function, method and parameters of the request, but
+    /// none of them supports the requested revision.
+    RevisionNotSupported,
+}
+
+impl LookupError {
+    /// Returns the COMRESULT the WEBWARE responds with if it can't call the function of the request.
+    pub fn comresult(&self, request: &WebserviceRequest) -> ComResult {
+        match self {
+            LookupError::UnknownFunction => ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
+                .bereich("WWSVC")
+                .code("400 Bad Request")
+                .info("Es wurde eine fehlerhafte Anforderung übergeben.")
+                .info2("Funktionsname nicht bekannt.")
+                .info3(&request.function.function_name)
+                .errno("20")
+                .errnotxt("SVCERR_UNKNOWN_FUNCTION (20)")
+                .build()
+                .unwrap(),
+            LookupError::RevisionNotSupported => ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
+                .bereich("WWSVC")
+                .code("400 Bad Request")
+                .info("Es wurde eine fehlerhafte Anforderung übergeben.")
+                .info2("Revision wird nicht unterstützt.")
+                .info3(&format!(
+                    "{} (Revision {})",
+                    request.function.function_name, request.function.revision
+                ))
+                .errno("21")
+                .errnotxt("SVCERR_REVISION_NOT_SUPPORTED (21)")
+                .build()
+                .unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl WebserviceRequest {
+    /// Creates a request with the given function, revision and parameters and an empty `WWSVC_PASSINFO` block.
+    pub(crate) fn for_test(function_name: &str, revision: u32, parameters: &[(&str, &str)]) -> Self {
+        WebserviceRequest {
+            function: WebserviceFunction {
+                function_name: function_name.to_string(),
+                revision,
+                parameter: parameters
+                    .iter()
+                    .map(|(name, value)| WebserviceParameter {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            },
+            pass_info: WebservicePassInfo {
+                service_pass: "".to_string(),
+                app_hash: "".to_string(),
+                timestamp: "".to_string(),
+                request_id: 1,
+                execute_mode: "SYNCHRON".to_string(),
+            },
+        }
     }
 }
 
@@ -194,6 +438,43 @@ impl WebserviceRequest {
 mod tests {
     use axum::response::IntoResponse;
 
+    use crate::{FileOrString, MockResource, MockResourceMethod, MockResourceRevision};
+
+    fn request(function_name: &str, revision: u32) -> super::WebserviceRequest {
+        super::WebserviceRequest::for_test(function_name, revision, &[])
+    }
+
+    fn resource(value: &str, revision: MockResourceRevision) -> MockResource {
+        MockResource {
+            data_source: FileOrString::String { value: value.to_string() },
+            function: "ARTIKEL".to_string(),
+            method: MockResourceMethod::Get,
+            revision,
+            parameters: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lookup_resource_by_revision() {
+        let resources = vec![
+            resource("2", 2.into()),
+            resource("3", 3.into()),
+            resource("range", MockResourceRevision::Range { min: 4, max: 5 }),
+        ];
+
+        let (index, found) = request("ARTIKEL.GET", 3).lookup_resource(&resources).unwrap();
+        assert_eq!((index, found.data_source.as_string()), (1, "3".to_string()));
+        let (index, found) = request("ARTIKEL.GET", 5).lookup_resource(&resources).unwrap();
+        assert_eq!((index, found.data_source.as_string()), (2, "range".to_string()));
+        let err = request("ARTIKEL.GET", 6).lookup_resource(&resources).unwrap_err();
+        assert_eq!(err, super::LookupError::RevisionNotSupported);
+        let err = request("ARTIKEL.PUT", 3).lookup_resource(&resources).unwrap_err();
+        assert_eq!(err, super::LookupError::UnknownFunction);
+        let err = request("ARTIKEL.UNKNOWN", 3).lookup_resource(&resources).unwrap_err();
+        assert_eq!(err, super::LookupError::UnknownFunction);
+    }
+
     #[test]
     fn invalid_status_code_service_response() {
         let response = super::ServiceResponse {
@@ -213,4 +494,31 @@ mod tests {
         let response = response.into_response();
         assert_eq!(response.status(), 500);
     }
+
+    #[test]
+    fn comresult_from_config() {
+        let comresult = super::ComResult::from(&crate::ComResultConfig {
+            status: 409,
+            code: None,
+            info: "Es ist ein Fehler aufgetreten.".to_string(),
+            info2: Some("Artikel existiert bereits".to_string()),
+            info3: None,
+            errno: Some("1".to_string()),
+            errnotxt: None,
+            bereich: Some("ARTIKEL".to_string()),
+        });
+        assert_eq!(
+            serde_json::to_value(comresult).unwrap(),
+            serde_json::json!({
+                "STATUS": 409,
+                "CODE": "409 Conflict",
+                "INFO": "Es ist ein Fehler aufgetreten.",
+                "INFO2": "Artikel existiert bereits",
+                "INFO3": null,
+                "ERRNO": "1",
+                "BEREICH": "ARTIKEL",
+                "ERRNOTXT": null
+            })
+        );
+    }
 }
@@ -4,13 +4,23 @@ use axum::{
     async_trait,
     extract::{FromRequestParts, Path, State},
     http::{request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
-use encoding_rs::WINDOWS_1252;
 use serde::de::DeserializeOwned;
 
-use crate::AppConfig;
+use crate::{
+    counters::CallCounters,
+    faults::{sent_status, FaultInjector},
+    journal::{Journal, ReceivedRequest, RequestRoute},
+    sessions::{SessionError, SessionRegistry},
+    AppConfig,
+};
 
-use super::{ComResultBuilder, ServiceResponse};
+use super::{
+    apply_latency,
+    authentication::{validate_headers, AuthenticationError},
+    ComResultBuilder, ServiceResponse,
+};
 
 #[derive(serde::Serialize)]
 pub struct RegisterResponse {
@@ -45,6 +55,21 @@ impl RegisterResponse {
             body: RegisterResponse { service_pass: None },
         }
     }
+
+    fn session_error(err: SessionError) -> ServiceResponse<Self> {
+        let info2 = match err {
+            SessionError::LimitReached => "wwsvc-mock: Maximum number of service passes reached",
+        };
+        ServiceResponse {
+            comresult: ComResultBuilder::with_status(StatusCode::NOT_ACCEPTABLE)
+                .code("406 Not Acceptable")
+                .info("REGISTER is not possible")
+                .info2(info2)
+                .build()
+                .unwrap(),
+            body: RegisterResponse { service_pass: None },
+        }
+    }
 }
 
 #[derive(serde::Serialize, Clone)]
@@ -63,86 +88,93 @@ pub async fn handle_register(
         u32,
     )>,
     State(app_config): State<Arc<AppConfig>>,
-) -> ServiceResponse<RegisterResponse> {
-    if app_config.webware.webservices.vendor_hash != vendor_hash
-        || app_config.webware.webservices.application_hash != app_hash
-        || app_config.webware.webservices.application_secret != secret
-        || app_config.webware.webservices.version != revision
-    {
-        RegisterResponse::error()
-    } else {
-        RegisterResponse::success(
-            &app_config.webware.credentials.service_pass,
-            &app_config.webware.credentials.application_id,
-        )
-    }
+    State(sessions): State<Arc<SessionRegistry>>,
+    State(counters): State<Arc<CallCounters>>,
+    State(faults): State<Arc<FaultInjector>>,
+    State(journal): State<Arc<Journal>>,
+    headers: HeaderMap,
+) -> Response {
+    let first_call = counters.next("REGISTER") == 0;
+    apply_latency(app_config.latency.as_ref(), first_call).await;
+
+    let mut received = ReceivedRequest::new(RequestRoute::Register, &headers);
+    received.revision = Some(revision);
+
+    let fault = faults.pick(app_config.faults.as_ref());
+
+    let response = match sessions.find_application(&vendor_hash, &app_hash, &secret, revision) {
+        None => RegisterResponse::error(),
+        Some(application) => match sessions.register(application) {
+            Ok(session) => {
+                // the client never gets a usable service pass from a faulty response, so it mustn't take up a session
+                if fault.is_some() {
+                    sessions.deregister(&session.service_pass);
+                } else {
+                    received.service_pass = Some(session.service_pass.clone());
+                }
+                RegisterResponse::success(&session.service_pass, &session.application_id)
+            }
+            Err(err) => RegisterResponse::session_error(err),
+        },
+    };
+
+    let response = match fault {
+        Some(fault) => fault.respond(response),
+        None => response.into_response(),
+    };
+
+    received.status = sent_status(&response);
+    journal.record(received);
+
+    response
 }
 
 pub async fn handle_deregister(
     Path(service_pass): Path<String>,
     State(app_config): State<Arc<AppConfig>>,
+    State(sessions): State<Arc<SessionRegistry>>,
+    State(counters): State<Arc<CallCounters>>,
+    State(faults): State<Arc<FaultInjector>>,
+    State(journal): State<Arc<Journal>>,
     headers: HeaderMap,
-) -> ServiceResponse<()> {
-    if service_pass != app_config.webware.credentials.service_pass {
-        return ServiceResponse {
-            comresult: ComResultBuilder::with_status(StatusCode::NOT_FOUND)
-                .code("404 Resource not found")
-                .info("ERROR ServicePass not known")
-                .info2("wwsvc-mock: ServicePass not known")
-                .build()
-                .unwrap(),
-            body: (),
-        };
-    }
+) -> Response {
+    let first_call = counters.next("DEREGISTER") == 0;
+    apply_latency(app_config.latency.as_ref(), first_call).await;
 
-    if headers.get("WWSVC-EXECUTE-MODE").is_none()
-        || headers.get("WWSVC-REQID").is_none()
-        || headers.get("WWSVC-TS").is_none()
-        || headers.get("WWSVC-HASH").is_none()
-    {
-        return ServiceResponse {
-            comresult: ComResultBuilder::with_status(StatusCode::NOT_FOUND)
-                .code("404 Resource not found")
-                .info("ERROR ServicePass not known")
-                .info2("wwsvc-mock: Mandatory header missing")
-                .build()
-                .unwrap(),
-            body: (),
-        };
-    }
+    let response = deregister(&sessions, &headers, &service_pass);
 
-    let execute_mode = headers.get("WWSVC-EXECUTE-MODE").unwrap().to_str().unwrap();
-    let ts = headers.get("WWSVC-TS").unwrap().to_str().unwrap();
-    let hash = headers.get("WWSVC-HASH").unwrap().to_str().unwrap();
+    let response = match faults.pick(app_config.faults.as_ref()) {
+        Some(fault) => fault.respond(response),
+        None => response.into_response(),
+    };
 
-    if !["SYNCHRON", "ASYNCHRON"].contains(&execute_mode) {
-        return ServiceResponse {
-            comresult: ComResultBuilder::with_status(StatusCode::NOT_FOUND)
-                .code("404 Resource not found")
-                .info("ERROR ServicePass not known")
-                .info2("wwsvc-mock: Execute mode not known")
-                .build()
-                .unwrap(),
-            body: (),
-        };
-    }
+    let mut received = ReceivedRequest::new(RequestRoute::Deregister, &headers);
+    received.service_pass = Some(service_pass);
+    received.status = sent_status(&response);
+    journal.record(received);
+
+    response
+}
 
-    let expected_pre_hash = format!("{}{}", app_config.webware.credentials.application_id, ts);
-    let (cow, _, _) = WINDOWS_1252.encode(expected_pre_hash.as_str());
-    let expected_hash = format!("{:x}", md5::compute(cow));
+fn deregister(
+    sessions: &SessionRegistry,
+    headers: &HeaderMap,
+    service_pass: &str,
+) -> ServiceResponse<()> {
+    let result = match sessions.lookup(service_pass) {
+        Some(session) => validate_headers(headers, &session.application_id),
+        None => Err(AuthenticationError::ServicePassNotKnown),
+    };
 
-    if hash != expected_hash {
+    if let Err(err) = result {
         return ServiceResponse {
-            comresult: ComResultBuilder::with_status(StatusCode::NOT_FOUND)
-                .code("404 Resource not found")
-                .info("ERROR ServicePass not known")
-                .info2("wwsvc-mock: Hash not correct")
-                .build()
-                .unwrap(),
+            comresult: err.deregister_comresult(),
             body: (),
         };
     }
 
+    sessions.deregister(service_pass);
+
     ServiceResponse {
         comresult: ComResultBuilder::with_status(StatusCode::OK)
             .code("200 OK")
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use serde_json::Value;
+
+use crate::{
+    routes::{ComResult, ComResultBuilder, WebserviceRequest},
+    FieldSchema, FileOrString, FunctionSchema, MockResource,
+};
+
+/// The reasons why a request doesn't match the [schema][FunctionSchema] of its function.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    /// A required parameter wasn't sent.
+    MissingParameter(String),
+    /// A parameter was sent that the function doesn't accept.
+    UnknownParameter(String),
+    /// The value of a parameter doesn't fit its type or length.
+    InvalidValue {
+        /// The name of the parameter.
+        name: String,
+        /// Why the value doesn't fit, e.g. `longer than 25 characters`.
+        reason: String,
+    },
+}
+
+impl SchemaError {
+    /// Returns the COMRESULT of the error, a `400 Bad Request` naming the parameter that doesn't fit the schema.
+    pub fn comresult(&self) -> ComResult {
+        let builder = ComResultBuilder::with_status(StatusCode::BAD_REQUEST)
+            .bereich("WWSVC")
+            .code("400 Bad Request")
+            .info("Es wurde eine fehlerhafte Anforderung übergeben.");
+        match self {
+            SchemaError::MissingParameter(name) => builder.info2("Parameter fehlt.").info3(name),
+            SchemaError::UnknownParameter(name) => builder.info2("Parameter nicht erlaubt.").info3(name),
+            SchemaError::InvalidValue { name, reason } => builder
+                .info2("Parameter ungültig.")
+                .info3(&format!("{}: {}", name, reason)),
+        }
+        .build()
+        .unwrap()
+    }
+}
+
+/// Checks requests and the data of mock resources against the [schemas][FunctionSchema] of the functions.
+#[derive(Debug, Default)]
+pub(crate) struct SchemaRegistry {
+    schemas: Vec<FunctionSchema>,
+}
+
+impl SchemaRegistry {
+    pub fn new(schemas: Vec<FunctionSchema>) -> Self {
+        SchemaRegistry { schemas }
+    }
+
+    /// Checks the parameters of a request against the schema of its function, if there is one.
+    pub fn validate_request(&self, request: &WebserviceRequest) -> Result<(), SchemaError> {
+        let Some((function, method)) = request.function_and_method() else {
+            return Ok(());
+        };
+        let Some(schema) = self
+            .schemas
+            .iter()
+            .find(|schema| schema.describes(function, &method, request.function.revision))
+        else {
+            return Ok(());
+        };
+
+        if let Some(missing) = schema
+            .required_parameters
+            .iter()
+            .find(|name| request.parameter(name).is_none())
+        {
+            return Err(SchemaError::MissingParameter(missing.clone()));
+        }
+
+        for parameter in &request.function.parameter {
+            if !schema.allows_parameter(&parameter.name) {
+                return Err(SchemaError::UnknownParameter(parameter.name.clone()));
+            }
+            if let Some(field) = schema.parameters.get(&parameter.name) {
+                field
+                    .validate(&parameter.value)
+                    .map_err(|reason| SchemaError::InvalidValue {
+                        name: parameter.name.clone(),
+                        reason,
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the data of a mock resource against the fields of the schemas of its function. Scripts and templates
+    /// are only known once a request comes in, so they aren't checked.
+    pub fn validate_resource(&self, resource: &MockResource) -> anyhow::Result<()> {
+        if resource.template {
+            return Ok(());
+        }
+
+        let sequence = resource.sequence.iter().flat_map(|sequence| &sequence.responses);
+        for schema in self.schemas.iter().filter(|schema| schema.describes_resource(resource)) {
+            for data_source in std::iter::once(&resource.data_source).chain(sequence.clone()) {
+                if matches!(data_source, FileOrString::Script { .. }) {
+                    continue;
+                }
+                let Some(body) = data_source.read_json()? else {
+                    continue;
+                };
+
+                let mut errors = vec![];
+                check_fields(&body, &schema.fields, &mut errors);
+                if !errors.is_empty() {
+                    anyhow::bail!("{}", errors.join(", "));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks every field with a schema in the value and the objects and arrays nested in it.
+fn check_fields(value: &Value, fields: &HashMap<String, FieldSchema>, errors: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (name, value) in object {
+                let text = match value {
+                    Value::String(text) => Some(text.clone()),
+                    Value::Number(number) => Some(number.to_string()),
+                    _ => None,
+                };
+                if let (Some(field), Some(text)) = (fields.get(name), text) {
+                    if let Err(reason) = field.validate(&text) {
+                        errors.push(format!("{} {:?} is {}", name, text, reason));
+                    }
+                }
+                check_fields(value, fields, errors);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                check_fields(value, fields, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::{
+        mock,
+        routes::WebserviceRequest,
+        FieldSchema, FieldType, FunctionSchema, MockResourceMethod,
+    };
+
+    use super::{SchemaError, SchemaRegistry};
+
+    fn registry() -> SchemaRegistry {
+        let art_1_25 = FieldSchema {
+            field_type: FieldType::String,
+            max_length: Some(25),
+        };
+        SchemaRegistry::new(vec![FunctionSchema {
+            function: "ARTIKEL".to_string(),
+            method: MockResourceMethod::Insert,
+            revision: 2.into(),
+            required_parameters: vec!["ARTNR".to_string()],
+            allowed_parameters: Some(vec!["FELDER".to_string()]),
+            parameters: [
+                ("ARTNR".to_string(), art_1_25.clone()),
+                (
+                    "PREIS".to_string(),
+                    FieldSchema {
+                        field_type: FieldType::Decimal,
+                        max_length: None,
+                    },
+                ),
+            ]
+            .into(),
+            fields: [("ART_1_25".to_string(), art_1_25)].into(),
+        }])
+    }
+
+    #[test]
+    fn validate_request() {
+        let registry = registry();
+        let validate = |parameters: &[(&str, &str)]| registry.validate_request(&WebserviceRequest::for_test("ARTIKEL.INSERT", 2, parameters));
+
+        assert_eq!(validate(&[("ARTNR", "A1"), ("PREIS", "1,50"), ("FELDER", "SNR")]), Ok(()));
+        assert_eq!(validate(&[("PREIS", "1,50")]), Err(SchemaError::MissingParameter("ARTNR".to_string())));
+        assert_eq!(
+            validate(&[("ARTNR", "A1"), ("MENGE", "1")]),
+            Err(SchemaError::UnknownParameter("MENGE".to_string()))
+        );
+        assert_eq!(
+            validate(&[("ARTNR", &"A".repeat(26))]),
+            Err(SchemaError::InvalidValue {
+                name: "ARTNR".to_string(),
+                reason: "longer than 25 characters".to_string()
+            })
+        );
+        assert_eq!(
+            validate(&[("ARTNR", "A1"), ("PREIS", "teuer")]).unwrap_err().comresult().info3.as_deref(),
+            Some("PREIS: not a number")
+        );
+
+        assert_eq!(registry.validate_request(&WebserviceRequest::for_test("ARTIKEL.INSERT", 3, &[])), Ok(()));
+        assert_eq!(registry.validate_request(&WebserviceRequest::for_test("ARTIKEL.GET", 2, &[])), Ok(()));
+    }
+
+    #[test]
+    fn validate_resource() {
+        let registry = registry();
+        let resource = |body: serde_json::Value| mock("ARTIKEL").method(MockResourceMethod::Insert).revision(2).respond_with_json(body);
+
+        assert_eq!(registry.validate_resource(&resource(json!({ "ARTIKEL": { "ART_1_25": "A1" } }))).is_ok(), true);
+        let err = registry
+            .validate_resource(&resource(json!({ "ARTIKEL": [{ "ART_1_25": "A".repeat(30) }] })))
+            .unwrap_err();
+        assert_eq!(err.to_string(), format!("ART_1_25 \"{}\" is longer than 25 characters", "A".repeat(30)));
+
+        let other_revision = mock("ARTIKEL")
+            .method(MockResourceMethod::Insert)
+            .revision(3)
+            .respond_with_json(json!({ "ARTIKEL": { "ART_1_25": "A".repeat(30) } }));
+        assert_eq!(registry.validate_resource(&other_revision).is_ok(), true);
+    }
+
+    #[test]
+    fn field_types() {
+        let field = |field_type| FieldSchema { field_type, max_length: None };
+        assert_eq!(field(FieldType::Integer).validate("-42"), Ok(()));
+        assert_eq!(field(FieldType::Integer).validate("4,2").is_err(), true);
+        assert_eq!(field(FieldType::Decimal).validate("4,2"), Ok(()));
+        assert_eq!(field(FieldType::Date).validate("31.12.2024"), Ok(()));
+        assert_eq!(field(FieldType::Date).validate("2024-12-31"), Err("not a date".to_string()));
+    }
+}
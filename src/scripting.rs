@@ -0,0 +1,206 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use serde_json::{json, Value};
+
+use crate::{routes::WebserviceRequest, ComResultConfig, FileOrString};
+
+/// The maximum number of operations a script may run, so a faulty script can't hang the server.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// The result of running a script.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ScriptOutput {
+    pub body: Option<Value>,
+    pub comresult: Option<ComResultConfig>,
+}
+
+/// Runs [script data sources][FileOrString::Script], caching the compiled scripts and the state shared between them.
+#[derive(Debug)]
+pub(crate) struct ScriptEngine {
+    engine: Engine,
+    scripts: Mutex<HashMap<String, AST>>,
+    state: Mutex<Map>,
+}
+
+impl ScriptEngine {
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        ScriptEngine {
+            engine,
+            scripts: Mutex::new(HashMap::new()),
+            state: Mutex::new(Map::new()),
+        }
+    }
+
+    /// Compiles the script, or returns the cached compilation.
+    fn compile(&self, data_source: &FileOrString) -> anyhow::Result<AST> {
+        let (key, source) = match data_source {
+            FileOrString::Script { file: Some(file), .. } => {
+                let source = std::fs::read_to_string(file)
+                    .map_err(|err| anyhow::anyhow!("Failed to read {}: {}", file, err))?;
+                (format!("file:{}", file), source)
+            }
+            FileOrString::Script { file: None, source: Some(source) } => (source.clone(), source.clone()),
+            FileOrString::Script { file: None, source: None } => {
+                anyhow::bail!("A script needs either a file or a source")
+            }
+            _ => anyhow::bail!("Data source isn't a script"),
+        };
+
+        let mut scripts = self.scripts.lock().unwrap();
+        if let Some(ast) = scripts.get(&key) {
+            return Ok(ast.clone());
+        }
+        let ast = self
+            .engine
+            .compile(&source)
+            .map_err(|err| anyhow::anyhow!("Failed to compile script: {}", err))?;
+        scripts.insert(key, ast.clone());
+        Ok(ast)
+    }
+
+    /// Compiles the data source if it is a script, so syntax errors are reported on startup.
+    pub fn validate(&self, data_source: &FileOrString) -> anyhow::Result<()> {
+        match data_source {
+            FileOrString::Script { .. } => self.compile(data_source).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs the script for the given request and call to the resource, starting at `0`.
+    pub fn run(
+        &self,
+        data_source: &FileOrString,
+        request: &WebserviceRequest,
+        call: usize,
+    ) -> anyhow::Result<ScriptOutput> {
+        let ast = self.compile(data_source)?;
+
+        let parameters = request
+            .function
+            .parameter
+            .iter()
+            .map(|parameter| (parameter.name.clone(), Value::String(parameter.value.clone())))
+            .collect::<serde_json::Map<_, _>>();
+        let request = rhai::serde::to_dynamic(json!({
+            "function": request.function.function_name,
+            "revision": request.function.revision,
+            "parameters": parameters,
+            "service_pass": request.pass_info.service_pass,
+            "app_hash": request.pass_info.app_hash,
+            "request_id": request.pass_info.request_id,
+            "previous_calls": call,
+        }))?;
+
+        let mut state = self.state.lock().unwrap();
+        let mut scope = Scope::new();
+        scope.push("request", request);
+        scope.push("state", std::mem::take(&mut *state));
+        scope.push("comresult", Dynamic::UNIT);
+
+        let result = self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast);
+
+        // keep the state, even if the script failed halfway through
+        if let Some(new_state) = scope.get_value::<Map>("state") {
+            *state = new_state;
+        }
+        let result = result.map_err(|err| anyhow::anyhow!("Script failed: {}", err))?;
+
+        let body = match result.is_unit() {
+            true => None,
+            false => Some(rhai::serde::from_dynamic::<Value>(&result)?),
+        };
+        let comresult = match scope.get_value::<Dynamic>("comresult") {
+            Some(comresult) if !comresult.is_unit() => Some(
+                rhai::serde::from_dynamic::<ComResultConfig>(&comresult)
+                    .map_err(|err| anyhow::anyhow!("Invalid comresult: {}", err))?,
+            ),
+            _ => None,
+        };
+
+        Ok(ScriptOutput { body, comresult })
+    }
+
+    /// Clears the state shared between scripts.
+    pub fn reset(&self) {
+        self.state.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::{
+        routes::WebserviceRequest,
+        FileOrString,
+    };
+
+    use super::ScriptEngine;
+
+    fn request(parameters: &[(&str, &str)]) -> WebserviceRequest {
+        let mut request = WebserviceRequest::for_test("PREIS.EXEC", 1, parameters);
+        request.pass_info.service_pass = "pass".to_string();
+        request.pass_info.app_hash = "hash".to_string();
+        request
+    }
+
+    fn script(source: &str) -> FileOrString {
+        FileOrString::Script {
+            file: None,
+            source: Some(source.to_string()),
+        }
+    }
+
+    #[test]
+    fn compute_body_and_comresult() {
+        let engine = ScriptEngine::new();
+        let price = script(
+            r#"
+            let menge = parse_int(request.parameters.MENGE);
+            if menge <= 0 {
+                comresult = #{ status: 400, info: "Menge muss positiv sein" };
+                return ();
+            }
+            #{ PREIS: (menge * 250).to_string(), CALL: request.previous_calls }
+            "#,
+        );
+
+        let output = engine.run(&price, &request(&[("MENGE", "4")]), 2).unwrap();
+        assert_eq!(output.body, Some(json!({ "PREIS": "1000", "CALL": 2 })));
+        assert_eq!(output.comresult, None);
+
+        let output = engine.run(&price, &request(&[("MENGE", "0")]), 0).unwrap();
+        assert_eq!(output.body, None);
+        let comresult = output.comresult.unwrap();
+        assert_eq!(comresult.status, 400);
+        assert_eq!(comresult.info, "Menge muss positiv sein");
+    }
+
+    #[test]
+    fn shared_state() {
+        let engine = ScriptEngine::new();
+        let insert = script(r#"state.last = request.parameters.ARTNR; ()"#);
+        let get = script(r#"#{ ARTNR: state.last }"#);
+
+        engine.run(&insert, &request(&[("ARTNR", "A1")]), 0).unwrap();
+        let output = engine.run(&get, &request(&[]), 0).unwrap();
+        assert_eq!(output.body, Some(json!({ "ARTNR": "A1" })));
+
+        engine.reset();
+        let output = engine.run(&get, &request(&[]), 0).unwrap();
+        assert_eq!(output.body, Some(json!({ "ARTNR": null })));
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        let engine = ScriptEngine::new();
+        assert_eq!(engine.validate(&script("let x = ;")).is_err(), true);
+        assert_eq!(engine.validate(&FileOrString::Script { file: None, source: None }).is_err(), true);
+        assert_eq!(engine.validate(&FileOrString::Empty).is_ok(), true);
+        assert_eq!(engine.run(&script("loop {}"), &request(&[]), 0).is_err(), true);
+    }
+}
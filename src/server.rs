@@ -0,0 +1,151 @@
+use std::net::SocketAddr;
+
+use tokio::{net::TcpListener, sync::oneshot, task::JoinHandle};
+
+use crate::{app_with_handle, AppConfig, CredentialsConfig, MockHandle, WebservicesConfig};
+
+/// A mock server running in the background on an ephemeral port, e.g. for integration tests.
+///
+/// The server shuts down when it is dropped. It has to be started from within a tokio runtime.
+///
+/// ```rust,ignore
+/// let server = MockServer::start(AppConfig::default().with_mock_resource(resource)).await?;
+/// let client = server.client(); // requires the `client` feature
+/// // ...
+/// assert_eq!(server.handle().received_requests_for("ARTIKEL.GET").len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct MockServer {
+    address: SocketAddr,
+    config: AppConfig,
+    handle: MockHandle,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<std::io::Result<()>>>,
+}
+
+impl MockServer {
+    /// Starts a mock server with the given configuration on a random port of `127.0.0.1`.
+    ///
+    /// The [server configuration][AppConfig::server] is ignored.
+    pub async fn start(config: AppConfig) -> anyhow::Result<Self> {
+        let (router, handle) = app_with_handle(&config).await?;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+
+        let (shutdown, shutdown_signal) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            axum::serve(listener, router.into_make_service())
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_signal.await;
+                })
+                .await
+        });
+        tracing::debug!("Mock server listening on http://{}/", address);
+
+        Ok(MockServer {
+            address,
+            config,
+            handle,
+            shutdown: Some(shutdown),
+            task: Some(task),
+        })
+    }
+
+    /// Returns the base URL of the server, e.g. `http://127.0.0.1:34567/`.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns the configuration the server was started with.
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    /// Returns the hashes and secret a client has to register with.
+    pub fn webservices(&self) -> &WebservicesConfig {
+        &self.config.webware.webservices
+    }
+
+    /// Returns the service pass and application ID the server accepts.
+    ///
+    /// If [sessions][crate::SessionsConfig] are enabled, service passes are issued per REGISTER instead.
+    pub fn credentials(&self) -> &CredentialsConfig {
+        &self.config.webware.credentials
+    }
+
+    /// Returns the handle to the state of the server, e.g. to verify which requests it received.
+    pub fn handle(&self) -> &MockHandle {
+        &self.handle
+    }
+
+    /// Returns a client that is set up to register with the server.
+    #[cfg(feature = "client")]
+    pub fn client(&self) -> wwsvc_rs::WebwareClient {
+        let webservices = self.webservices();
+        wwsvc_rs::WebwareClient::builder()
+            .webware_url(&self.url())
+            .vendor_hash(&webservices.vendor_hash)
+            .app_hash(&webservices.application_hash)
+            .revision(webservices.version)
+            .secret(&webservices.application_secret)
+            .allow_insecure(true)
+            .build()
+    }
+
+    /// Shuts the server down and waits until all open connections are closed.
+    pub async fn shutdown(mut self) -> anyhow::Result<()> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(task) = self.task.take() {
+            task.await??;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::AppConfig;
+
+    use super::MockServer;
+
+    #[tokio::test]
+    async fn start_and_shutdown() {
+        let server = MockServer::start(AppConfig::default()).await.unwrap();
+        let url = server.url();
+        assert_eq!(url, format!("http://127.0.0.1:{}/", server.address().port()));
+
+        let response = reqwest::get(format!("{}WWSVC/WWSERVICE/DEREGISTER/unknown/", url))
+            .await
+            .unwrap();
+        assert_eq!(response.status().is_client_error(), true);
+
+        server.shutdown().await.unwrap();
+        assert_eq!(reqwest::get(&url).await.is_err(), true);
+    }
+
+    #[tokio::test]
+    async fn shutdown_on_drop() {
+        let server = MockServer::start(AppConfig::default()).await.unwrap();
+        let address = server.address();
+        drop(server);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(tokio::net::TcpStream::connect(address).await.is_err(), true);
+    }
+}
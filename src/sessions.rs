@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app_config::{generate_hash, SessionsConfig},
+    ApplicationConfig, WebwareConfig,
+};
+
+/// A service pass that was issued by the server.
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// The service pass of the session.
+    pub service_pass: String,
+    /// The application ID of the session, used to compute the `WWSVC-HASH`.
+    pub application_id: String,
+    /// The application hash of the application that registered the session.
+    pub application_hash: String,
+    last_used: Instant,
+}
+
+impl Session {
+    fn new(service_pass: String, application_id: String, application: &ApplicationConfig) -> Self {
+        Session {
+            service_pass,
+            application_id,
+            application_hash: application.webservices.application_hash.clone(),
+            last_used: Instant::now(),
+        }
+    }
+
+    fn from_credentials(application: &ApplicationConfig) -> Self {
+        Session::new(
+            application.credentials.service_pass.clone(),
+            application.credentials.application_id.clone(),
+            application,
+        )
+    }
+}
+
+/// The reasons why a REGISTER call can be refused by the registry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionError {
+    /// The maximum number of active service passes has been reached.
+    LimitReached,
+}
+
+/// Keeps track of the registered applications and the service passes issued to them.
+///
+/// If the registry is disabled, every REGISTER returns the static service pass from the
+/// [CredentialsConfig][crate::CredentialsConfig] of the application, which never expires.
+#[derive(Debug)]
+pub(crate) struct SessionRegistry {
+    config: SessionsConfig,
+    applications: Vec<ApplicationConfig>,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SessionRegistry {
+    pub fn new(config: &WebwareConfig) -> Self {
+        SessionRegistry {
+            config: config.sessions.clone(),
+            applications: config.all_applications(),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Finds the application that registers with the given hashes, secret and version.
+    pub fn find_application(
+        &self,
+        vendor_hash: &str,
+        application_hash: &str,
+        secret: &str,
+        version: u32,
+    ) -> Option<&ApplicationConfig> {
+        self.applications.iter().find(|application| {
+            application.webservices.vendor_hash == vendor_hash
+                && application.webservices.application_hash == application_hash
+                && application.webservices.application_secret == secret
+                && application.webservices.version == version
+        })
+    }
+
+    /// Finds the application with the given application hash.
+    pub fn application(&self, application_hash: &str) -> Option<&ApplicationConfig> {
+        self.applications
+            .iter()
+            .find(|application| application.webservices.application_hash == application_hash)
+    }
+
+    fn static_session(&self, service_pass: &str) -> Option<Session> {
+        self.applications
+            .iter()
+            .find(|application| application.credentials.service_pass == service_pass)
+            .map(Session::from_credentials)
+    }
+
+    fn prune(&self, sessions: &mut HashMap<String, Session>, now: Instant) {
+        if let Some(idle_timeout) = self.config.idle_timeout_ms {
+            let idle_timeout = Duration::from_millis(idle_timeout);
+            sessions.retain(|_, session| now.duration_since(session.last_used) < idle_timeout);
+        }
+    }
+
+    /// Issues a new service pass for the given application.
+    pub fn register(&self, application: &ApplicationConfig) -> Result<Session, SessionError> {
+        if !self.config.enabled {
+            return Ok(Session::from_credentials(application));
+        }
+
+        let mut sessions = self.sessions.lock().unwrap();
+        self.prune(&mut sessions, Instant::now());
+        if let Some(max_service_passes) = self.config.max_service_passes {
+            if sessions.len() >= max_service_passes {
+                return Err(SessionError::LimitReached);
+            }
+        }
+
+        let session = Session::new(generate_hash(), generate_hash(), application);
+        sessions.insert(session.service_pass.clone(), session.clone());
+        Ok(session)
+    }
+
+    /// Looks up an active service pass and marks it as used.
+    pub fn lookup(&self, service_pass: &str) -> Option<Session> {
+        if !self.config.enabled {
+            return self.static_session(service_pass);
+        }
+
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        self.prune(&mut sessions, now);
+        sessions.get_mut(service_pass).map(|session| {
+            session.last_used = now;
+            session.clone()
+        })
+    }
+
+    /// Removes a service pass from the registry, returning it if it was active.
+    pub fn deregister(&self, service_pass: &str) -> Option<Session> {
+        if !self.config.enabled {
+            return self.static_session(service_pass);
+        }
+
+        let mut sessions = self.sessions.lock().unwrap();
+        self.prune(&mut sessions, Instant::now());
+        sessions.remove(service_pass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{app_config::SessionsConfig, ApplicationConfig, WebwareConfig};
+
+    use super::{SessionError, SessionRegistry};
+
+    fn registry(sessions: SessionsConfig) -> SessionRegistry {
+        SessionRegistry::new(&WebwareConfig {
+            sessions,
+            applications: vec![ApplicationConfig::default()],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn disabled_registry_uses_static_credentials() {
+        let registry = registry(SessionsConfig::default());
+        let first = registry.register(&registry.applications[0]).unwrap();
+        let second = registry.register(&registry.applications[0]).unwrap();
+        assert_eq!(first.service_pass, second.service_pass);
+        let other = registry.register(&registry.applications[1]).unwrap();
+        assert_ne!(first.service_pass, other.service_pass);
+        assert_eq!(registry.lookup(&other.service_pass).unwrap().application_hash, registry.applications[1].webservices.application_hash);
+        assert_eq!(registry.deregister(&first.service_pass).is_some(), true);
+        assert_eq!(registry.lookup(&first.service_pass).is_some(), true);
+        assert_eq!(registry.lookup("unknown").is_none(), true);
+    }
+
+    #[test]
+    fn enabled_registry_issues_fresh_passes() {
+        let registry = registry(SessionsConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let first = registry.register(&registry.applications[0]).unwrap();
+        let second = registry.register(&registry.applications[0]).unwrap();
+        assert_ne!(first.service_pass, second.service_pass);
+        assert_ne!(first.application_id, second.application_id);
+        assert_eq!(first.application_hash, registry.applications[0].webservices.application_hash);
+        assert_eq!(registry.lookup(&first.service_pass).unwrap().application_id, first.application_id);
+        assert_eq!(registry.deregister(&first.service_pass).is_some(), true);
+        assert_eq!(registry.lookup(&first.service_pass).is_none(), true);
+        assert_eq!(registry.deregister(&first.service_pass).is_none(), true);
+    }
+
+    #[test]
+    fn find_application() {
+        let registry = registry(SessionsConfig::default());
+        let webservices = &registry.applications[1].webservices;
+        let found = registry
+            .find_application(
+                &webservices.vendor_hash,
+                &webservices.application_hash,
+                &webservices.application_secret,
+                webservices.version,
+            )
+            .unwrap();
+        assert_eq!(found.credentials.service_pass, registry.applications[1].credentials.service_pass);
+        assert_eq!(registry.find_application(&webservices.vendor_hash, "unknown", "1", 1).is_none(), true);
+        assert_eq!(registry.application(&webservices.application_hash).is_some(), true);
+    }
+
+    #[test]
+    fn enabled_registry_limits_passes() {
+        let registry = registry(SessionsConfig {
+            enabled: true,
+            max_service_passes: Some(1),
+            ..Default::default()
+        });
+        let first = registry.register(&registry.applications[0]).unwrap();
+        assert_eq!(registry.register(&registry.applications[0]).unwrap_err(), SessionError::LimitReached);
+        registry.deregister(&first.service_pass);
+        assert_eq!(registry.register(&registry.applications[0]).is_ok(), true);
+    }
+
+    #[test]
+    fn enabled_registry_expires_idle_passes() {
+        let registry = registry(SessionsConfig {
+            enabled: true,
+            idle_timeout_ms: Some(0),
+            ..Default::default()
+        });
+        let session = registry.register(&registry.applications[0]).unwrap();
+        assert_eq!(registry.lookup(&session.service_pass).is_none(), true);
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::routes::WebserviceRequest;
+
+/// The values that placeholders in a template are replaced with.
+#[derive(Debug)]
+pub(crate) struct TemplateContext<'a> {
+    pub request: &'a WebserviceRequest,
+    /// The number of previous calls to the resource, starting at `0`.
+    pub call: usize,
+    /// The current time as seconds since the Unix epoch.
+    pub now: u64,
+}
+
+impl<'a> TemplateContext<'a> {
+    pub fn new(request: &'a WebserviceRequest, call: usize) -> Self {
+        TemplateContext {
+            request,
+            call,
+            now: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the value of a placeholder, or `None` if the placeholder isn't known.
+    fn value(&self, placeholder: &str) -> Option<String> {
+        if let Some(name) = placeholder.strip_prefix("param.") {
+            return Some(self.request.parameter(name).unwrap_or_default().to_string());
+        }
+
+        let (days, seconds) = (self.now / 86400, self.now % 86400);
+        let (hour, minute, second) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+        let value = match placeholder {
+            "function" => self.request.function.function_name.clone(),
+            "revision" => self.request.function.revision.to_string(),
+            "service_pass" => self.request.pass_info.service_pass.clone(),
+            "app_hash" => self.request.pass_info.app_hash.clone(),
+            "request_id" => self.request.pass_info.request_id.to_string(),
+            "call" => (self.call + 1).to_string(),
+            "now.unix" => self.now.to_string(),
+            "now.date" => {
+                let (year, month, day) = civil_from_days(days);
+                format!("{:02}.{:02}.{:04}", day, month, year)
+            }
+            "now.time" => format!("{:02}:{:02}:{:02}", hour, minute, second),
+            "now.iso" => {
+                let (year, month, day) = civil_from_days(days);
+                format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                    year, month, day, hour, minute, second
+                )
+            }
+            "random.number" => rand::random::<u32>().to_string(),
+            "random.hash" => crate::app_config::generate_hash(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Converts days since the Unix epoch into a (year, month, day) date, see
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Escapes a value for use inside a JSON string.
+fn escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Replaces all `{{placeholder}}`s in the template. Unknown placeholders are left as they are.
+pub(crate) fn render(template: &str, context: &TemplateContext) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..start + end];
+        match context.value(placeholder.trim()) {
+            Some(value) => rendered.push_str(&escape(&value)),
+            None => {
+                tracing::warn!("Unknown template placeholder: {}", placeholder.trim());
+                rendered.push_str(&rest[start..start + end + 2]);
+            }
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::routes::WebserviceRequest;
+
+    use super::{render, TemplateContext};
+
+    fn request() -> WebserviceRequest {
+        let mut request = WebserviceRequest::for_test("ARTIKEL.INSERT", 2, &[("ARTNR", "Artikel \"19\"")]);
+        request.pass_info.service_pass = "pass".to_string();
+        request.pass_info.app_hash = "hash".to_string();
+        request.pass_info.request_id = 42;
+        request
+    }
+
+    #[test]
+    fn render_request_values() {
+        let request = request();
+        let context = TemplateContext::new(&request, 0);
+        assert_eq!(
+            render(r#"{"ARTNR": "{{ param.ARTNR }}", "BEZ": "{{param.ART_51_60}}"}"#, &context),
+            r#"{"ARTNR": "Artikel \"19\"", "BEZ": ""}"#
+        );
+        assert_eq!(
+            render("{{function}} {{revision}} {{service_pass}} {{app_hash}} {{request_id}} {{call}}", &context),
+            "ARTIKEL.INSERT 2 pass hash 42 1"
+        );
+        assert_eq!(render("{{unknown}} {{", &context), "{{unknown}} {{");
+        assert_eq!(render("{{random.hash}}", &context).len(), 32);
+    }
+
+    #[test]
+    fn render_timestamps() {
+        let request = request();
+        let context = TemplateContext {
+            request: &request,
+            call: 0,
+            now: 1792161000,
+        };
+        assert_eq!(
+            render("{{now.date}} {{now.time}} {{now.iso}} {{now.unix}}", &context),
+            "16.10.2026 14:30:00 2026-10-16T14:30:00Z 1792161000"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use pretty_assertions::assert_eq;
+use reqwest::Method;
+use serde_json::json;
+
+mod common;
+
+async fn setup_admin() -> common::TestEnvironment {
+    let mut config = common::default_config(false);
+    config.admin_api = true;
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+#[tokio::test]
+async fn admin_api_disabled_by_default() {
+    let env = common::setup(false)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, _) = common::send(env.request(Method::GET, "__admin/mocks")).await;
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn add_replace_and_remove_mock() {
+    let env = setup_admin().await;
+    assert_eq!(common::exec_json(&env, "PREIS.EXEC", 1, &[]).await.1["COMRESULT"]["ERRNO"], "20");
+
+    let (status, body) = common::send(env.request(Method::POST, "__admin/mocks").json(&json!({
+        "function": "PREIS",
+        "method": "EXEC",
+        "revision": 1,
+        "data_source": { "type": "String", "value": r#"{"PREIS": "9.99"}"# }
+    })))
+    .await;
+    assert_eq!(status, 201);
+    let id = body["id"].as_u64().unwrap();
+    assert_eq!(common::exec_json(&env, "PREIS.EXEC", 1, &[]).await.1["PREIS"], "9.99");
+
+    let (status, _) = common::send(
+        env.request(Method::PUT, &format!("__admin/mocks/{}", id))
+            .header("Content-Type", "application/toml")
+            .body(
+                r#"
+                function = "PREIS"
+                method = "EXEC"
+                revision = 1
+                data_source = { type = "String", value = '{"PREIS": "19.99"}' }"#,
+            ),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(common::exec_json(&env, "PREIS.EXEC", 1, &[]).await.1["PREIS"], "19.99");
+
+    let (_, mocks) = common::send(env.request(Method::GET, "__admin/mocks")).await;
+    assert_eq!(mocks.as_array().unwrap().len(), env.config.mock_resources.len() + 1);
+
+    let (status, _) = common::send(env.request(Method::DELETE, &format!("__admin/mocks/{}", id))).await;
+    assert_eq!(status, 200);
+    assert_eq!(common::exec_json(&env, "PREIS.EXEC", 1, &[]).await.1["COMRESULT"]["ERRNO"], "20");
+
+    let (status, _) = common::send(env.request(Method::DELETE, &format!("__admin/mocks/{}", id))).await;
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn replaced_mock_starts_over() {
+    let env = setup_admin().await;
+    let job = |status: &str| {
+        json!({
+            "function": "JOB",
+            "method": "EXEC",
+            "revision": 1,
+            "sequence": {
+                "responses": [
+                    { "type": "String", "value": r#"{"STATUS": "RUNNING"}"# },
+                    { "type": "String", "value": format!(r#"{{"STATUS": "{}"}}"#, status) }
+                ]
+            }
+        })
+    };
+    let status = |env| async move { common::exec_json(env, "JOB.EXEC", 1, &[]).await.1["STATUS"].clone() };
+
+    let (_, body) = common::send(env.request(Method::POST, "__admin/mocks").json(&job("DONE"))).await;
+    let id = body["id"].as_u64().unwrap();
+    assert_eq!(status(&env).await, "RUNNING");
+    assert_eq!(status(&env).await, "DONE");
+
+    let (status_code, _) =
+        common::send(env.request(Method::PUT, &format!("__admin/mocks/{}", id)).json(&job("FAILED"))).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(status(&env).await, "RUNNING");
+    assert_eq!(status(&env).await, "FAILED");
+}
+
+#[tokio::test]
+async fn invalid_mock() {
+    let env = setup_admin().await;
+
+    let (status, body) = common::send(env.request(Method::POST, "__admin/mocks").json(&json!({ "function": "PREIS" }))).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["error"].is_string(), true);
+}
+
+#[tokio::test]
+async fn reset_restores_configured_mocks() {
+    let env = setup_admin().await;
+
+    common::send(env.request(Method::DELETE, "__admin/mocks")).await;
+    let (_, mocks) = common::send(env.request(Method::GET, "__admin/mocks")).await;
+    assert_eq!(mocks.as_array().unwrap().len(), 0);
+
+    let (status, _) = common::send(env.request(Method::POST, "__admin/reset")).await;
+    assert_eq!(status, 204);
+    let (_, mocks) = common::send(env.request(Method::GET, "__admin/mocks")).await;
+    assert_eq!(mocks.as_array().unwrap().len(), env.config.mock_resources.len());
+    assert_eq!(mocks[0]["id"], 1);
+    assert_eq!(mocks[0]["function"], "ARTIKEL");
+}
+
+#[cfg(feature = "scripting")]
+#[tokio::test]
+async fn mock_with_broken_script() {
+    let env = setup_admin().await;
+
+    let (status, body) = common::send(env.request(Method::POST, "__admin/mocks").json(&json!({
+        "function": "PREIS",
+        "method": "EXEC",
+        "revision": 1,
+        "data_source": { "type": "Script", "source": "let preis = ;" }
+    })))
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(body["error"].as_str().unwrap().contains("Invalid script"), true);
+
+    let (status, _) = common::send(env.request(Method::PUT, "__admin/mocks/1").json(&json!({
+        "function": "ARTIKEL",
+        "method": "GET",
+        "revision": 3,
+        "data_source": { "type": "Script", "source": "#{" }
+    })))
+    .await;
+    assert_eq!(status, 400);
+    let (_, mock) = common::send(env.request(Method::GET, "__admin/mocks/1")).await;
+    assert_eq!(mock["function"], "ARTIKEL");
+    assert_eq!(common::exec_json(&env, "PREIS.EXEC", 1, &[]).await.1["COMRESULT"]["ERRNO"], "20");
+}
+
+#[cfg(not(feature = "scripting"))]
+#[tokio::test]
+async fn mock_with_script_without_feature() {
+    let env = setup_admin().await;
+
+    let (status, body) = common::send(env.request(Method::POST, "__admin/mocks").json(&json!({
+        "function": "PREIS",
+        "method": "EXEC",
+        "revision": 1,
+        "data_source": { "type": "Script", "source": "42" }
+    })))
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(body["error"].as_str().unwrap().contains("`scripting` feature"), true);
+}
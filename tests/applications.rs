@@ -0,0 +1,124 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{ApplicationConfig, FileOrString, MockResource, MockResourceMethod, ParameterMatcher};
+use wwsvc_rs::{collection, futures::FutureExt, Method, WebwareClient};
+
+mod common;
+
+async fn setup_applications() -> common::TestEnvironment {
+    let mut config = common::default_config(false);
+    config.webware.authenticate_requests = true;
+    config.webware.sessions.enabled = true;
+    config.webware.applications = vec![
+        ApplicationConfig {
+            name: Some("shop".to_string()),
+            ..Default::default()
+        },
+        ApplicationConfig {
+            name: Some("restricted".to_string()),
+            mock_resources: Some(vec![MockResource {
+                data_source: FileOrString::String { value: r#"{"GET_RESULT": "Eingeschränkt"}"#.to_string() },
+                function: "GET_RELATION".to_string(),
+                method: MockResourceMethod::Exec,
+                revision: 1.into(),
+                parameters: Some(collection! {
+                    "NR".to_string() => ParameterMatcher::regex("65").unwrap(),
+                }),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        },
+    ];
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+fn client_for(env: &common::TestEnvironment, application: &ApplicationConfig) -> WebwareClient {
+    WebwareClient::builder()
+        .webware_url(&env.server.url())
+        .vendor_hash(&application.webservices.vendor_hash)
+        .app_hash(&application.webservices.application_hash)
+        .revision(application.webservices.version)
+        .secret(&application.webservices.application_secret)
+        .allow_insecure(true)
+        .build()
+}
+
+async fn call(client: WebwareClient, function: &'static str, revision: u32, nr: &'static str) -> (u16, serde_json::Value) {
+    let response = client
+        .with_registered(|client| {
+            async move {
+                client
+                    .request_as_response(Method::PUT, function, revision, collection! {
+                        "NR" => nr,
+                        "P1" => "Hallo",
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+    let status = response.status().as_u16();
+    let body = response.json().await.expect("Failed to parse response body");
+    (status, body)
+}
+
+#[tokio::test]
+async fn every_application_can_register() {
+    let env = setup_applications().await;
+
+    for application in &env.config.webware.applications {
+        client_for(&env, application)
+            .register()
+            .await
+            .expect("Failed to register an additional application");
+    }
+    env.client
+        .register()
+        .await
+        .expect("Failed to register the default application");
+}
+
+#[tokio::test]
+async fn application_without_resources_uses_global_resources() {
+    let env = setup_applications().await;
+    let client = client_for(&env, &env.config.webware.applications[0]);
+
+    let (status, body) = call(client, "GET_RELATION.EXEC", 1, "65").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["GET_RESULT"], "Hallo");
+}
+
+#[tokio::test]
+async fn application_with_resources_uses_own_resources() {
+    let env = setup_applications().await;
+    let client = client_for(&env, &env.config.webware.applications[1]);
+
+    let (status, body) = call(client, "GET_RELATION.EXEC", 1, "65").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["GET_RESULT"], "Eingeschränkt");
+}
+
+#[tokio::test]
+async fn application_with_resources_cannot_call_global_resources() {
+    let env = setup_applications().await;
+    let client = client_for(&env, &env.config.webware.applications[1]);
+
+    let response = client
+        .with_registered(|client| {
+            async {
+                client
+                    .request_as_response(Method::PUT, "ARTIKEL.INSERT", 2, collection! {
+                        "ARTNR" => "MeinArtikel",
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 400);
+}
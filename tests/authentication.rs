@@ -0,0 +1,112 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_rs::{collection, futures::FutureExt, Method};
+
+mod common;
+
+async fn setup_authenticated() -> common::TestEnvironment {
+    let mut config = common::default_config(false);
+    config.webware.authenticate_requests = true;
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+fn hash(application_id: &str, ts: &str) -> String {
+    format!("{:x}", md5::compute(format!("{}{}", application_id, ts)))
+}
+
+/// Returns the body of an `ARTIKEL.INSERT` request with the given service pass and application hash.
+fn exec_body(env: &common::TestEnvironment, service_pass: &str, app_hash: &str) -> serde_json::Value {
+    let mut body = common::exec_json_body(&env.config, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")]);
+    body["WWSVC_PASSINFO"]["SERVICEPASS"] = json!(service_pass);
+    body["WWSVC_PASSINFO"]["APPHASH"] = json!(app_hash);
+    body
+}
+
+#[tokio::test]
+async fn authenticated_request_with_client() {
+    let env = setup_authenticated().await;
+
+    let response = env
+        .client
+        .with_registered(|client| {
+            async {
+                client
+                    .request_as_response(Method::PUT, "ARTIKEL.INSERT", 2, collection! {
+                        "ARTNR" => "MeinArtikel",
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn authenticated_request_with_unknown_service_pass() {
+    let env = setup_authenticated().await;
+    let ts = "Mon, 01 Jan 2000 00:00:00 GMT";
+
+    let res = reqwest::Client::new()
+        .put(env.url("WWSVC/EXECJSON"))
+        .header("WWSVC-EXECUTE-MODE", "SYNCHRON")
+        .header("WWSVC-REQID", "1")
+        .header("WWSVC-TS", ts)
+        .header("WWSVC-HASH", hash(&env.config.webware.credentials.application_id, ts))
+        .json(&exec_body(&env, "a", &env.config.webware.webservices.application_hash))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(res.status().as_u16(), 404);
+    let body: serde_json::Value = res.json().await.expect("Failed to parse response body");
+    assert_eq!(body["COMRESULT"]["INFO"], "ERROR ServicePass not known");
+}
+
+#[tokio::test]
+async fn authenticated_request_with_wrong_hash() {
+    let env = setup_authenticated().await;
+
+    let res = common::exec_json_request(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")])
+        .header("WWSVC-EXECUTE-MODE", "SYNCHRON")
+        .header("WWSVC-REQID", "1")
+        .header("WWSVC-TS", "Mon, 01 Jan 2000 00:00:00 GMT")
+        .header("WWSVC-HASH", "a")
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(res.status().as_u16(), 401);
+    let body: serde_json::Value = res.json().await.expect("Failed to parse response body");
+    assert_eq!(body["COMRESULT"]["INFO2"], "wwsvc-mock: Hash not correct");
+}
+
+#[tokio::test]
+async fn authenticated_request_without_headers() {
+    let env = setup_authenticated().await;
+
+    let res = common::exec_json_request(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")])
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(res.status().as_u16(), 401);
+    let body: serde_json::Value = res.json().await.expect("Failed to parse response body");
+    assert_eq!(body["COMRESULT"]["INFO2"], "wwsvc-mock: Mandatory header missing");
+}
+
+#[tokio::test]
+async fn unauthenticated_request_is_accepted_by_default() {
+    let env = common::setup(false)
+        .await
+        .expect("Failed to setup test environment");
+
+    let res = reqwest::Client::new()
+        .put(env.url("WWSVC/EXECJSON"))
+        .json(&exec_body(&env, "a", "a"))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(res.status().as_u16(), 200);
+}
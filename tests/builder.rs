@@ -0,0 +1,67 @@
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+use serde_json::json;
+use wwsvc_mock::{mock, MockResourceMethod};
+
+mod common;
+
+#[derive(Serialize)]
+struct Relation {
+    #[serde(rename = "GET_RESULT")]
+    result: String,
+}
+
+#[tokio::test]
+async fn built_resources_match_requests() {
+    let config = common::default_config(false)
+        .with_mock_resource(
+            mock("RELATION")
+                .method(MockResourceMethod::Exec)
+                .param("NR", "6.5")
+                .param_absent("P2")
+                .respond_with_serde(&Relation {
+                    result: "exact".to_string(),
+                }),
+        )
+        .with_mock_resource(
+            mock("RELATION")
+                .method(MockResourceMethod::Exec)
+                .param_regex("NR", "^[0-9]+$")
+                .respond_with_json(json!({ "GET_RESULT": "regex" })),
+        );
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "RELATION.EXEC", 7, &[("NR", "6.5")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["GET_RESULT"], "exact");
+
+    let (status, body) = common::exec_json(&env, "RELATION.EXEC", 7, &[("NR", "65")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["GET_RESULT"], "regex");
+
+    let (status, _) = common::exec_json(&env, "RELATION.EXEC", 7, &[("NR", "6.5"), ("P2", "x")]).await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn json_parameters() {
+    let config = common::default_config(false).with_mock_resource(
+        mock("RELATION")
+            .method(MockResourceMethod::Exec)
+            .revision(7)
+            .param_json("NR", json!({ "NR": 65, "ART": ["A", "B"] }))
+            .respond_with_json(json!({ "GET_RESULT": "json" })),
+    );
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "RELATION.EXEC", 7, &[("NR", r#"{ "ART": ["A", "B"], "NR": 65.0 }"#)]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["GET_RESULT"], "json");
+
+    let (status, _) = common::exec_json(&env, "RELATION.EXEC", 7, &[("NR", r#"{"NR": 65, "ART": ["B", "A"]}"#)]).await;
+    assert_eq!(status, 400);
+}
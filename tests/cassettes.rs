@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{Cassette, CassetteInteraction, DeserializedRegex, FileOrString, MockResourceMethod};
+
+mod common;
+
+fn interaction(method: MockResourceMethod, parameters: &[(&str, &str)], response: &str) -> CassetteInteraction {
+    CassetteInteraction {
+        function: "AUFTRAG".to_string(),
+        method,
+        revision: 1.into(),
+        parameters: parameters
+            .iter()
+            .map(|(name, regex)| (name.to_string(), DeserializedRegex::new(regex).unwrap()))
+            .collect::<HashMap<_, _>>(),
+        response: FileOrString::String { value: response.to_string() },
+        comresult: None,
+    }
+}
+
+async fn setup_cassette() -> common::TestEnvironment {
+    let mut config = common::default_config(false);
+    config.admin_api = true;
+    config.cassette = Some(Cassette {
+        file: None,
+        interactions: vec![
+            interaction(MockResourceMethod::Insert, &[("KUNDE", "^10001$")], r#"{"AUFTRAG": {"AUFNR": "1"}}"#),
+            interaction(MockResourceMethod::Insert, &[("AUFNR", "^1$"), ("ARTNR", "^A1$")], r#"{"POSITION": {"POS": "1"}}"#),
+            interaction(MockResourceMethod::Put, &[("AUFNR", "^1$"), ("BUCHEN", "^J$")], r#"{"AUFTRAG": {"STATUS": "GEBUCHT"}}"#),
+        ],
+    });
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+#[tokio::test]
+async fn cassette_replays_in_order() {
+    let env = setup_cassette().await;
+
+    let (status, body) = common::exec_json(&env, "AUFTRAG.INSERT", 1, &[("KUNDE", "10001")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["AUFTRAG"]["AUFNR"], "1");
+
+    // functions that aren't part of the cassette are answered by the mock resources
+    let (status, _) = common::exec_json(&env, "GET_RELATION.EXEC", 1, &[("NR", "65"), ("P1", "Hallo")]).await;
+    assert_eq!(status, 200);
+
+    let (_, body) = common::exec_json(&env, "AUFTRAG.INSERT", 1, &[("AUFNR", "1"), ("ARTNR", "A1")]).await;
+    assert_eq!(body["POSITION"]["POS"], "1");
+    let (_, body) = common::exec_json(&env, "AUFTRAG.PUT", 1, &[("AUFNR", "1"), ("BUCHEN", "J")]).await;
+    assert_eq!(body["AUFTRAG"]["STATUS"], "GEBUCHT");
+    assert_eq!(env.handle.remaining_interactions(), Some(0));
+
+    let (status, body) = common::exec_json(&env, "AUFTRAG.PUT", 1, &[("AUFNR", "1"), ("BUCHEN", "J")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO2"], "wwsvc-mock: Request deviates from the cassette");
+}
+
+#[tokio::test]
+async fn cassette_rejects_deviations() {
+    let env = setup_cassette().await;
+
+    // booking before adding a position
+    common::exec_json(&env, "AUFTRAG.INSERT", 1, &[("KUNDE", "10001")]).await;
+    let (status, body) = common::exec_json(&env, "AUFTRAG.PUT", 1, &[("AUFNR", "1"), ("BUCHEN", "J")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(
+        body["COMRESULT"]["INFO3"].as_str().unwrap().starts_with("Interaction 2: expected AUFTRAG.INSERT"),
+        true
+    );
+    assert_eq!(env.handle.remaining_interactions(), Some(2));
+
+    // the admin API rewinds the cassette
+    common::send(env.request(reqwest::Method::POST, "__admin/reset")).await;
+    assert_eq!(env.handle.remaining_interactions(), Some(3));
+    let (status, _) = common::exec_json(&env, "AUFTRAG.INSERT", 1, &[("KUNDE", "10001")]).await;
+    assert_eq!(status, 200);
+}
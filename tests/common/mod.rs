@@ -1,69 +1,77 @@
-use axum_test::{TestServer, TestServerConfig};
-use wwsvc_mock::{app, AppConfig, DeserializedRegex, FileOrString, MockResource, MockResourceMethod};
+// not every test uses every helper
+#![allow(dead_code)]
+
+use serde_json::json;
+use wwsvc_mock::{AppConfig, DatasetConfig, MockHandle, MockServer, FileOrString, MockResource, MockResourceMethod, ParameterMatcher};
 use wwsvc_rs::{collection, WebwareClient};
 
 pub struct TestEnvironment {
-    pub server: TestServer,
+    pub server: MockServer,
     pub client: wwsvc_rs::WebwareClient,
     pub config: AppConfig,
+    pub handle: MockHandle,
 }
 
-pub async fn setup(debug: bool) -> anyhow::Result<TestEnvironment> {
+pub fn default_config(debug: bool) -> AppConfig {
     let mut config = AppConfig::default().with_mock_resource(MockResource {
         data_source: FileOrString::File {
-            file: "data/artikel_clean.json".to_string(),
+            file: "data/artikel.json".to_string(),
         },
         function: "ARTIKEL".to_string(),
         method: MockResourceMethod::Get,
-        revision: 3,
+        revision: 3.into(),
         parameters: None,
-    }).with_mock_resource(MockResource {
-        data_source: FileOrString::File {
-            file: "data/artikel_art_nr_clean.json".to_string(),
-        },
-        function: "ARTIKEL".to_string(),
-        method: MockResourceMethod::Get,
-        revision: 3,
-        parameters: Some(collection! {
-            "FELDER".to_string() => DeserializedRegex::new("ART_1_25").unwrap(),
-        })
+        dataset: Some(DatasetConfig::default()),
+        ..Default::default()
     }).with_mock_resource(MockResource {
         data_source: FileOrString::Empty,
         function: "ARTIKEL".to_string(),
         method: MockResourceMethod::Put,
-        revision: 1,
+        revision: 1.into(),
         parameters: Some(collection! {
-            "ARTNR".to_string() => DeserializedRegex::new("Artikel19Prozent").unwrap(),
-            "ART_51_60".to_string() => DeserializedRegex::new("Eine Bezeichnung").unwrap(),
-        })
+            "ARTNR".to_string() => ParameterMatcher::regex("Artikel19Prozent").unwrap(),
+            "ART_51_60".to_string() => ParameterMatcher::regex("Eine Bezeichnung").unwrap(),
+        }),
+        ..Default::default()
     }).with_mock_resource(MockResource {
         data_source: FileOrString::String { value: r#"{"ARTNR": "MeinArtikel"}"#.to_string() },
         function: "ARTIKEL".to_string(),
         method: MockResourceMethod::Insert,
-        revision: 2,
+        revision: 2.into(),
         parameters: Some(collection! {
-            "ARTNR".to_string() => DeserializedRegex::new("MeinArtikel").unwrap(),
-        })
+            "ARTNR".to_string() => ParameterMatcher::regex("MeinArtikel").unwrap(),
+        }),
+        ..Default::default()
     }).with_mock_resource(MockResource {
         data_source: FileOrString::Empty,
         function: "ARTIKEL".to_string(),
         method: MockResourceMethod::Delete,
-        revision: 1,
+        revision: 1.into(),
         parameters: Some(collection! {
-            "ARTNR".to_string() => DeserializedRegex::new("Artikel19Prozent").unwrap(),
-        })
+            "ARTNR".to_string() => ParameterMatcher::regex("Artikel19Prozent").unwrap(),
+        }),
+        ..Default::default()
     }).with_mock_resource(MockResource {
         data_source: FileOrString::String { value: r#"{"GET_RESULT": "Hallo"}"#.to_string() },
         function: "GET_RELATION".to_string(),
         method: MockResourceMethod::Exec,
-        revision: 1,
+        revision: 1.into(),
         parameters: Some(collection! {
-            "NR".to_string() => DeserializedRegex::new("65").unwrap(),
-            "P1".to_string() => DeserializedRegex::new("Hallo").unwrap(),
-        })
+            "NR".to_string() => ParameterMatcher::regex("65").unwrap(),
+            "P1".to_string() => ParameterMatcher::regex("Hallo").unwrap(),
+        }),
+        ..Default::default()
     });
 
     config.debug = debug;
+    config
+}
+
+pub async fn setup(debug: bool) -> anyhow::Result<TestEnvironment> {
+    setup_with_config(default_config(debug)).await
+}
+
+pub async fn setup_with_config(config: AppConfig) -> anyhow::Result<TestEnvironment> {
 
     println!("----- WEBWARE Mock Server -----");
     println!("Running in test mode");
@@ -86,23 +94,10 @@ pub async fn setup(debug: bool) -> anyhow::Result<TestEnvironment> {
     );
     println!("-------------------------------");
 
-    let app = app(&config).await?;
-    let server = TestServer::new_with_config(
-        app,
-        TestServerConfig {
-            transport: Some(axum_test::Transport::HttpIpPort {
-                ip: "127.0.0.1".parse().ok(),
-                port: None,
-            }),
-            ..Default::default()
-        },
-    )?;
-    println!(
-        "Server listening on: {}",
-        server.server_address().unwrap().as_str()
-    );
+    let server = MockServer::start(config.clone()).await?;
+    println!("Server listening on: {}", server.url());
     let client = WebwareClient::builder()
-        .webware_url(server.server_address().unwrap().as_str())
+        .webware_url(&server.url())
         .vendor_hash(&config.webware.webservices.vendor_hash)
         .app_hash(&config.webware.webservices.application_hash)
         .revision(config.webware.webservices.version)
@@ -110,5 +105,77 @@ pub async fn setup(debug: bool) -> anyhow::Result<TestEnvironment> {
         .allow_insecure(true)
         .build();
 
-    Ok(TestEnvironment { server, client, config })
+    Ok(TestEnvironment {
+        handle: server.handle().clone(),
+        server,
+        client,
+        config,
+    })
+}
+
+impl TestEnvironment {
+    /// Returns the full URL of the given path on the server, e.g. `__admin/mocks`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.server.url(), path)
+    }
+
+    /// Builds a request to the given path on the server.
+    pub fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        reqwest::Client::new().request(method, self.url(path))
+    }
+}
+
+/// Sends the request and returns the status code along with the JSON body, which is `null` if the body isn't JSON.
+pub async fn send(request: reqwest::RequestBuilder) -> (u16, serde_json::Value) {
+    let response = request.send().await.expect("Failed to send request");
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap_or_default())
+}
+
+/// Returns the body of an EXECJSON request for the function, e.g. `ARTIKEL.GET`, with the service pass and
+/// application hash of the configuration.
+pub fn exec_json_body(
+    config: &AppConfig,
+    function: &str,
+    revision: u32,
+    parameters: &[(&str, &str)],
+) -> serde_json::Value {
+    json!({
+        "WWSVC_FUNCTION": {
+            "FUNCTIONNAME": function,
+            "REVISION": revision,
+            "PARAMETER": parameters
+                .iter()
+                .map(|(name, value)| json!({ "PNAME": name, "PCONTENT": value }))
+                .collect::<Vec<_>>()
+        },
+        "WWSVC_PASSINFO": {
+            "SERVICEPASS": config.webware.credentials.service_pass,
+            "APPHASH": config.webware.webservices.application_hash,
+            "TIMESTAMP": "Mon, 01 Jan 2000 00:00:00 GMT",
+            "REQUESTID": 1,
+            "EXECUTE_MODE": "SYNCHRON"
+        }
+    })
+}
+
+/// Builds an EXECJSON request to the server, e.g. to add headers before sending it.
+pub fn exec_json_request(
+    env: &TestEnvironment,
+    function: &str,
+    revision: u32,
+    parameters: &[(&str, &str)],
+) -> reqwest::RequestBuilder {
+    env.request(reqwest::Method::PUT, "WWSVC/EXECJSON")
+        .json(&exec_json_body(&env.config, function, revision, parameters))
+}
+
+/// Sends an EXECJSON request to the server and returns the status code along with the JSON body.
+pub async fn exec_json(
+    env: &TestEnvironment,
+    function: &str,
+    revision: u32,
+    parameters: &[(&str, &str)],
+) -> (u16, serde_json::Value) {
+    send(exec_json_request(env, function, revision, parameters)).await
 }
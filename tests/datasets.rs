@@ -0,0 +1,184 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_mock::{mock, AppConfig, DatasetConfig, FieldFilter, FilterMode};
+
+mod common;
+
+fn dataset_config() -> AppConfig {
+    common::default_config(false).with_mock_resource(
+        mock("ARTIKEL")
+            .revision(4)
+            .dataset(
+                DatasetConfig::default()
+                    .with_filter("ARTNR", FieldFilter::new("ART_1_25", FilterMode::Wildcard))
+                    .with_filter("ARTNR_VON", FieldFilter::new("ART_1_25", FilterMode::Min))
+                    .with_filter("ARTNR_BIS", FieldFilter::new("ART_1_25", FilterMode::Max)),
+            )
+            .respond_with_file("data/artikel.json"),
+    )
+}
+
+async fn get_page(
+    env: &common::TestEnvironment,
+    cursor: &str,
+    parameters: &[(&str, &str)],
+) -> (u16, serde_json::Value, String) {
+    let response = common::exec_json_request(env, "ARTIKEL.GET", 4, parameters)
+        .header("WWSVC-CURSOR", cursor)
+        .header("WWSVC-ACCEPT-RESULT-MAX-LINES", "100")
+        .send()
+        .await
+        .expect("Failed to send request");
+    let status = response.status().as_u16();
+    let cursor = response
+        .headers()
+        .get("WWSVC-CURSOR")
+        .map(|cursor| cursor.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (status, response.json().await.expect("Failed to parse response body"), cursor)
+}
+
+#[tokio::test]
+async fn felder_projection() {
+    let env = common::setup_with_config(dataset_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "396");
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0], json!({
+        "ART_1_25": "$KASSE0001",
+        "ART_51_60": "KASSE0001",
+        "ART_PREIS": "0,99",
+        "ART_WARENGR": "100",
+        "SNR": "304"
+    }));
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[("FELDER", "SNR")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0], json!({ "SNR": "304" }));
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[("FELDER", "ART_1_25,ART_PREIS")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "396");
+    let records = body["ARTIKELLISTE"]["ARTIKEL"].as_array().unwrap();
+    assert_eq!(records.len(), 396);
+    assert_eq!(records[0], json!({ "ART_1_25": "$KASSE0001", "ART_PREIS": "0,99", "SNR": "304" }));
+    for record in records {
+        let mut fields = record.as_object().unwrap().keys().collect::<Vec<_>>();
+        fields.sort();
+        assert_eq!(fields, vec!["ART_1_25", "ART_PREIS", "SNR"]);
+    }
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[("FELDER", "ART_1_25,ART_UNBEKANNT")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO2"], "Feld nicht bekannt.");
+    assert_eq!(body["COMRESULT"]["INFO3"], "ART_UNBEKANNT");
+}
+
+#[tokio::test]
+async fn filter_records() {
+    let env = common::setup_with_config(dataset_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[("ARTNR", "$KASSE*")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "13");
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"].as_array().unwrap().len(), 13);
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[
+        ("ARTNR_VON", "$KASSE0005"),
+        ("ARTNR_BIS", "$KASSE0007"),
+        ("FELDER", "SNR"),
+    ])
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"], json!({
+        "ANZAHL": "3",
+        "ARTIKEL": [{ "SNR": "308" }, { "SNR": "309" }, { "SNR": "310" }]
+    }));
+}
+
+#[tokio::test]
+async fn paging_parameters() {
+    let env = common::setup_with_config(dataset_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[
+        ("MAXLINES", "2"),
+        ("STARTPOS", "395"),
+    ])
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "2");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.GET", 4, &[("MAXLINES", "alle")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO3"], "MAXLINES");
+}
+
+#[tokio::test]
+async fn walk_cursor() {
+    let env = common::setup_with_config(dataset_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let mut cursor = "CREATE".to_string();
+    let mut pages = vec![];
+    let mut records = vec![];
+    while cursor != "CLOSED" {
+        let (status, body, next_cursor) = get_page(&env, &cursor, &[("FELDER", "SNR")]).await;
+        assert_eq!(status, 200);
+        pages.push(body["ARTIKELLISTE"]["ANZAHL"].as_str().unwrap().to_string());
+        records.extend(body["ARTIKELLISTE"]["ARTIKEL"].as_array().unwrap().clone());
+        cursor = next_cursor;
+    }
+    assert_eq!(pages, vec!["100", "100", "100", "96"]);
+    assert_eq!(records.len(), 396);
+    assert_eq!(records[0], json!({ "SNR": "304" }));
+
+    let (status, body, _) = get_page(&env, "UNBEKANNT", &[]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO2"], "Cursor nicht bekannt.");
+}
+
+#[tokio::test]
+async fn cursor_belongs_to_request() {
+    let env = common::setup_with_config(dataset_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let parameters = &[("FELDER", "SNR")];
+    let (_, _, cursor) = get_page(&env, "CREATE", parameters).await;
+
+    let (status, body, _) = get_page(&env, &cursor, &[("FELDER", "ART_1_25")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO2"], "Cursor gehört zu einer anderen Anfrage.");
+
+    let (status, body, _) = get_page(&env, &cursor, parameters).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0], json!({ "SNR": "141" }));
+}
+
+#[tokio::test]
+async fn cursor_ignores_paging_parameters() {
+    let env = common::setup_with_config(dataset_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let parameters = &[
+        ("FELDER", "SNR"),
+        ("MAXLINES", "10"),
+        ("STARTPOS", "11"),
+    ];
+    let (status, body, cursor) = get_page(&env, "CREATE", parameters).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "100");
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0], json!({ "SNR": "304" }));
+
+    let (_, body, _) = get_page(&env, &cursor, parameters).await;
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][0], json!({ "SNR": "141" }));
+}
@@ -0,0 +1,48 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn diagnostics_in_response() {
+    let mut config = common::default_config(false);
+    config.diagnostics = true;
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "DeinArtikel")]).await.1;
+    assert_eq!(body["COMRESULT"]["ERRNO"], "20");
+    let near_misses = &body["WWSVC_MOCK_DIAGNOSTICS"]["NEAR_MISSES"];
+    assert_eq!(
+        near_misses[0]["mismatches"],
+        json!([r#"parameter ARTNR="DeinArtikel" doesn't match /MeinArtikel/"#])
+    );
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 3, &[("ARTNR", "MeinArtikel")]).await.1;
+    assert_eq!(body["COMRESULT"]["ERRNO"], "21");
+    assert_eq!(
+        body["WWSVC_MOCK_DIAGNOSTICS"]["NEAR_MISSES"][0]["mismatches"],
+        json!(["revision 3 doesn't match 2"])
+    );
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")]).await.1;
+    assert_eq!(body.get("WWSVC_MOCK_DIAGNOSTICS"), None);
+}
+
+#[tokio::test]
+async fn diagnostics_disabled_by_default() {
+    let env = common::setup(false)
+        .await
+        .expect("Failed to setup test environment");
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "DeinArtikel")]).await.1;
+    assert_eq!(body["COMRESULT"]["ERRNO"], "20");
+    assert_eq!(body.get("WWSVC_MOCK_DIAGNOSTICS"), None);
+
+    let unmatched = env.handle.unmatched_requests();
+    assert_eq!(unmatched.len(), 1);
+    assert_eq!(unmatched[0].parameter("ARTNR"), Some("DeinArtikel"));
+    let near_misses = unmatched[0].near_misses.as_ref().unwrap();
+    assert_eq!(near_misses[0].resource.contains("INSERT"), true);
+}
@@ -0,0 +1,85 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{FaultConfig, FaultKind, FileOrString, MockResource, MockResourceMethod};
+
+mod common;
+
+fn faulty_resource(kind: FaultKind) -> MockResource {
+    MockResource {
+        data_source: FileOrString::String { value: r#"{"STATUS": "OK"}"#.to_string() },
+        function: "WACKELIG".to_string(),
+        method: MockResourceMethod::Exec,
+        revision: 1.into(),
+        faults: Some(FaultConfig {
+            probability: 1.0,
+            kinds: vec![kind],
+        }),
+        parameters: None,
+        ..Default::default()
+    }
+}
+
+async fn setup(kind: FaultKind) -> common::TestEnvironment {
+    let config = common::default_config(false).with_mock_resource(faulty_resource(kind));
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+async fn exec(env: &common::TestEnvironment) -> reqwest::Result<reqwest::Response> {
+    common::exec_json_request(env, "WACKELIG.EXEC", 1, &[]).send().await
+}
+
+#[tokio::test]
+async fn internal_server_error() {
+    let env = setup(FaultKind::InternalServerError).await;
+    let response = exec(&env).await.expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 500);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response body");
+    assert_eq!(body["COMRESULT"]["STATUS"], 500);
+}
+
+#[tokio::test]
+async fn service_unavailable() {
+    let env = setup(FaultKind::ServiceUnavailable).await;
+    let response = exec(&env).await.expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 503);
+}
+
+#[tokio::test]
+async fn error_page() {
+    let env = setup(FaultKind::ErrorPage).await;
+    let response = exec(&env).await.expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 500);
+    let body = response.text().await.expect("Failed to read response body");
+    assert_eq!(body.starts_with("<!DOCTYPE html"), true);
+}
+
+#[tokio::test]
+async fn truncated_json() {
+    let env = setup(FaultKind::TruncatedJson).await;
+    let response = exec(&env).await.expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.text().await.expect("Failed to read response body");
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&body).is_err(), true);
+}
+
+#[tokio::test]
+async fn drop_connection() {
+    let env = setup(FaultKind::DropConnection).await;
+    // the connection is closed before the status line, so there is no response at all
+    assert_eq!(exec(&env).await.is_err(), true);
+}
+
+#[tokio::test]
+async fn global_faults_apply_to_register() {
+    let mut config = common::default_config(false);
+    config.faults = Some(FaultConfig {
+        probability: 1.0,
+        kinds: vec![FaultKind::ServiceUnavailable],
+    });
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    assert_eq!(env.client.register().await.is_err(), true);
+}
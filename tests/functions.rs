@@ -1,5 +1,6 @@
 use pretty_assertions::assert_eq;
 use serde_json::json;
+use wwsvc_mock::{ComResultConfig, FileOrString, MockResource, MockResourceMethod, ParameterMatcher};
 use wwsvc_rs::{collection, futures::FutureExt, Method};
 
 mod common;
@@ -12,8 +13,18 @@ async fn artikel_get_all_fields() {
     let env = common::setup(false)
         .await
         .expect("Failed to setup test environment");
-    let expected_json: serde_json::Value =
+    let mut expected_json: serde_json::Value =
         serde_json::from_str(ARTIKEL_JSON_STR).expect("Failed to parse artikel.json");
+    expected_json["COMRESULT"] = json!({
+        "BEREICH": "WWSVC",
+        "STATUS": 200,
+        "CODE": "200 OK",
+        "INFO": "Kein Fehler",
+        "INFO2": "",
+        "INFO3": "",
+        "ERRNO": "0",
+        "ERRNOTXT": "SVCERR_NO_ERROR (0)"
+    });
 
     let response = env
         .client
@@ -46,7 +57,7 @@ async fn artikel_get_art_nr() {
         .await
         .expect("Failed to setup test environment");
     let expected_json: serde_json::Value =
-        serde_json::from_str(ARTIKEL_ART_NR_JSON_STR).expect("Failed to parse artikel.json");
+        serde_json::from_str(ARTIKEL_ART_NR_JSON_STR).expect("Failed to parse artikel_art_nr.json");
 
     let response = env
         .client
@@ -291,3 +302,114 @@ async fn non_existant_function() {
         serde_json::from_str(&text_body).expect("Failed to parse response body");
     assert_eq!(body, expected_json);
 }
+
+#[tokio::test]
+async fn unsupported_revision() {
+    let env = common::setup(false)
+        .await
+        .expect("Failed to setup test environment");
+    let expected_json = json!({
+        "COMRESULT": {
+            "BEREICH": "WWSVC",
+            "STATUS": 400,
+            "CODE": "400 Bad Request",
+            "INFO": "Es wurde eine fehlerhafte Anforderung übergeben.",
+            "INFO2": "Revision wird nicht unterstützt.",
+            "INFO3": "ARTIKEL.GET (Revision 2)",
+            "ERRNO": "21",
+            "ERRNOTXT": "SVCERR_REVISION_NOT_SUPPORTED (21)"
+        }
+    });
+    let response = env
+        .client
+        .with_registered(|client| {
+            async {
+                client
+                    .request_as_response(Method::PUT, "ARTIKEL.GET", 2, collection! {}, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+    let status = response.status();
+    let text_body = response
+        .text()
+        .await
+        .expect("Failed to parse response body");
+    assert_eq!(status.as_u16(), 400);
+    let body: serde_json::Value =
+        serde_json::from_str(&text_body).expect("Failed to parse response body");
+    assert_eq!(body, expected_json);
+}
+
+#[tokio::test]
+async fn custom_comresult() {
+    let config = common::default_config(false).with_mock_resource(MockResource {
+        data_source: FileOrString::Empty,
+        function: "ARTIKEL".to_string(),
+        method: MockResourceMethod::Insert,
+        revision: 2.into(),
+        comresult: Some(ComResultConfig {
+            status: 400,
+            code: None,
+            info: "Es ist ein Fehler aufgetreten.".to_string(),
+            info2: Some("Artikel existiert bereits".to_string()),
+            info3: Some("Artikel19Prozent".to_string()),
+            errno: Some("1".to_string()),
+            errnotxt: Some("ERR_ARTIKEL_EXISTIERT (1)".to_string()),
+            bereich: Some("ARTIKEL".to_string()),
+        }),
+        parameters: Some(collection! {
+            "ARTNR".to_string() => ParameterMatcher::regex("Artikel19Prozent").unwrap(),
+        }),
+        ..Default::default()
+    });
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    // the custom COMRESULT only applies to the resource that declares it
+    let (status, body) = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["COMRESULT"]["INFO"], "Kein Fehler");
+    assert_eq!(body["ARTNR"], "MeinArtikel");
+
+    let expected_json = json!({
+        "COMRESULT": {
+            "BEREICH": "ARTIKEL",
+            "STATUS": 400,
+            "CODE": "400 Bad Request",
+            "INFO": "Es ist ein Fehler aufgetreten.",
+            "INFO2": "Artikel existiert bereits",
+            "INFO3": "Artikel19Prozent",
+            "ERRNO": "1",
+            "ERRNOTXT": "ERR_ARTIKEL_EXISTIERT (1)"
+        }
+    });
+    let response = env
+        .client
+        .with_registered(|client| {
+            async {
+                client
+                    .request_as_response(Method::PUT, "ARTIKEL.INSERT", 2, collection! {
+                        "ARTNR" => "Artikel19Prozent",
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+    let status = response.status();
+    let text_body = response
+        .text()
+        .await
+        .expect("Failed to parse response body");
+    assert_eq!(status.as_u16(), 400);
+    let body: serde_json::Value =
+        serde_json::from_str(&text_body).expect("Failed to parse response body");
+    assert_eq!(body, expected_json);
+}
@@ -0,0 +1,65 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{mock, AppConfig, GeneratedEntity, MockResource};
+
+mod common;
+
+fn generated_config() -> AppConfig {
+    common::default_config(false).with_mock_resource(
+        mock("KUNDE")
+            .revision(2)
+            .respond_with_generated(GeneratedEntity::Kunde, 250, 7),
+    )
+}
+
+#[tokio::test]
+async fn generated_list() {
+    let env = common::setup_with_config(generated_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "KUNDE.GET", 2, &[]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["KUNDENLISTE"]["ANZAHL"], "250");
+    assert_eq!(body["KUNDENLISTE"]["KUNDE"][249]["KUN_1_10"], "10250");
+
+    let (_, again) = common::exec_json(&env, "KUNDE.GET", 2, &[]).await;
+    assert_eq!(again, body);
+}
+
+#[tokio::test]
+async fn generated_list_handles_felder() {
+    let env = common::setup_with_config(generated_config())
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "KUNDE.GET", 2, &[
+        ("FELDER", "KUN_1_10,KUN_ORT"),
+        ("MAXLINES", "1"),
+    ])
+    .await;
+    assert_eq!(status, 200);
+    let kunde = body["KUNDENLISTE"]["KUNDE"].as_array().unwrap();
+    assert_eq!(kunde.len(), 1);
+    let mut fields = kunde[0].as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+    fields.sort();
+    assert_eq!(fields, vec!["KUN_1_10", "KUN_ORT", "SNR"]);
+}
+
+#[test]
+fn generated_data_source_from_toml() {
+    use figment::providers::{Format, Toml};
+
+    let resource: MockResource = figment::Figment::from(Toml::string(
+        r#"
+        function = "ARTIKEL"
+        method = "GET"
+        revision = 3
+        data_source = { type = "Generated", entity = "ARTIKEL", count = 10, seed = 1 }
+        "#,
+    ))
+    .extract()
+    .expect("Failed to parse the resource");
+    let body = resource.data_source.read_json().unwrap().unwrap();
+    assert_eq!(body["ARTIKELLISTE"]["ANZAHL"], "10");
+    assert_eq!(body["ARTIKELLISTE"]["ARTIKEL"][9]["ART_1_25"], "ART00010");
+}
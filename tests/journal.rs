@@ -0,0 +1,89 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::RequestRoute;
+use wwsvc_rs::{collection, futures::FutureExt, Method, WebwareClient};
+
+mod common;
+
+async fn insert(client: WebwareClient, article: &'static str) {
+    client
+        .with_registered(|client| {
+            async move {
+                client
+                    .request_as_response(Method::PUT, "ARTIKEL.INSERT", 2, collection! {
+                        "ARTNR" => article,
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+}
+
+#[tokio::test]
+async fn journal_records_requests() {
+    let env = common::setup(false)
+        .await
+        .expect("Failed to setup test environment");
+
+    insert(env.client, "MeinArtikel").await;
+
+    let routes = env
+        .handle
+        .received_requests()
+        .into_iter()
+        .map(|request| request.route)
+        .collect::<Vec<_>>();
+    assert_eq!(routes, vec![RequestRoute::Register, RequestRoute::ExecJson, RequestRoute::Deregister]);
+
+    let inserts = env.handle.received_requests_for("ARTIKEL.INSERT");
+    assert_eq!(inserts.len(), 1);
+    assert_eq!(inserts[0].revision, Some(2));
+    assert_eq!(inserts[0].parameter("ARTNR"), Some("MeinArtikel"));
+    assert_eq!(inserts[0].status, 200);
+    assert_eq!(inserts[0].matched_resource.is_some(), true);
+    assert_eq!(inserts[0].headers.contains_key("wwsvc-hash"), true);
+
+    env.handle.reset_journal();
+    assert_eq!(env.handle.received_requests().len(), 0);
+}
+
+#[tokio::test]
+async fn journal_records_unmatched_requests() {
+    let env = common::setup(false)
+        .await
+        .expect("Failed to setup test environment");
+
+    insert(env.client, "UnbekannterArtikel").await;
+
+    let inserts = env.handle.received_requests_for("ARTIKEL.INSERT");
+    assert_eq!(inserts.len(), 1);
+    assert_eq!(inserts[0].parameter("ARTNR"), Some("UnbekannterArtikel"));
+    assert_eq!(inserts[0].status, 400);
+    assert_eq!(inserts[0].matched_resource, None);
+}
+
+#[tokio::test]
+async fn journal_admin_endpoint() {
+    let mut config = common::default_config(false);
+    config.admin_api = true;
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    // the client is consumed by the request, so the admin requests go through their own client
+    let requests_url = env.url("__admin/requests");
+    insert(env.client, "MeinArtikel").await;
+
+    let admin = reqwest::Client::new();
+    let (_, requests) = common::send(admin.get(&requests_url).query(&[("function", "ARTIKEL.INSERT")])).await;
+    assert_eq!(requests.as_array().unwrap().len(), 1);
+    assert_eq!(requests[0]["route"], "EXECJSON");
+    assert_eq!(requests[0]["parameters"]["ARTNR"], "MeinArtikel");
+
+    common::send(admin.delete(&requests_url)).await;
+    let (_, requests) = common::send(admin.get(&requests_url)).await;
+    // the admin requests themselves aren't recorded
+    assert_eq!(requests, serde_json::json!([]));
+}
@@ -0,0 +1,89 @@
+use std::time::{Duration, Instant};
+
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{FileOrString, LatencyConfig, MockResource, MockResourceMethod};
+use wwsvc_rs::{collection, futures::FutureExt, Method};
+
+mod common;
+
+fn slow_resource(latency: LatencyConfig) -> MockResource {
+    MockResource {
+        data_source: FileOrString::String { value: r#"{"STATUS": "OK"}"#.to_string() },
+        function: "LANGSAM".to_string(),
+        method: MockResourceMethod::Exec,
+        revision: 1.into(),
+        latency: Some(latency),
+        parameters: None,
+        ..Default::default()
+    }
+}
+
+async fn timed_calls(env: common::TestEnvironment, times: usize) -> Vec<Duration> {
+    env.client
+        .with_registered(|client| {
+            async move {
+                let mut durations = vec![];
+                for _ in 0..times {
+                    let start = Instant::now();
+                    let response = client
+                        .request_as_response(Method::PUT, "LANGSAM.EXEC", 1, collection! {}, None)
+                        .await?;
+                    assert_eq!(response.status().as_u16(), 200);
+                    durations.push(start.elapsed());
+                }
+                Ok::<_, anyhow::Error>(durations)
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request")
+}
+
+#[tokio::test]
+async fn resource_latency() {
+    let config = common::default_config(false).with_mock_resource(slow_resource(LatencyConfig {
+        fixed_ms: 200,
+        ..Default::default()
+    }));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let durations = timed_calls(env, 2).await;
+    assert_eq!(durations.iter().all(|duration| *duration >= Duration::from_millis(200)), true);
+}
+
+#[tokio::test]
+async fn resource_latency_on_first_call() {
+    let config = common::default_config(false).with_mock_resource(slow_resource(LatencyConfig {
+        first_call_ms: Some(500),
+        ..Default::default()
+    }));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let durations = timed_calls(env, 2).await;
+    assert_eq!(durations[0] >= Duration::from_millis(500), true);
+    assert_eq!(durations[1] < durations[0], true);
+}
+
+#[tokio::test]
+async fn global_latency_applies_to_register() {
+    let mut config = common::default_config(false);
+    config.latency = Some(LatencyConfig {
+        fixed_ms: 200,
+        ..Default::default()
+    });
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let start = Instant::now();
+    env.client
+        .register()
+        .await
+        .expect("Failed to register the client");
+    assert_eq!(start.elapsed() >= Duration::from_millis(200), true);
+}
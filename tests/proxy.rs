@@ -0,0 +1,143 @@
+#![cfg(feature = "proxy")]
+
+use pretty_assertions::assert_eq;
+use figment::providers::Format;
+use wwsvc_mock::{AppConfig, ProxyConfig, RequestRoute};
+use wwsvc_rs::{collection, futures::FutureExt, Method, WebwareClient};
+
+mod common;
+
+async fn insert_twice(client: WebwareClient, article: &'static str) -> Vec<(u16, serde_json::Value)> {
+    client
+        .with_registered(|client| {
+            async move {
+                let mut responses = vec![];
+                for _ in 0..2 {
+                    let response = client
+                        .request_as_response(Method::PUT, "ARTIKEL.INSERT", 2, collection! {
+                            "ARTNR" => article,
+                        }, None)
+                        .await?;
+                    let status = response.status().as_u16();
+                    responses.push((status, response.json::<serde_json::Value>().await?));
+                }
+                Ok::<_, anyhow::Error>(responses)
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request")
+}
+
+#[tokio::test]
+async fn proxy_records_upstream_responses() {
+    let mut upstream_config = common::default_config(false);
+    upstream_config.webware.authenticate_requests = true;
+    upstream_config.webware.sessions.enabled = true;
+    let upstream = common::setup_with_config(upstream_config)
+        .await
+        .expect("Failed to setup upstream environment");
+
+    let record_directory = std::env::temp_dir().join(format!(
+        "wwsvc-mock-proxy-{}",
+        upstream.config.webware.webservices.application_hash
+    ));
+    let config = AppConfig {
+        proxy: Some(ProxyConfig {
+            url: upstream.server.url(),
+            webservices: upstream.config.webware.webservices.clone(),
+            record_directory: Some(record_directory.to_string_lossy().to_string()),
+        }),
+        ..Default::default()
+    };
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let responses = insert_twice(env.client, "MeinArtikel").await;
+    for (status, body) in &responses {
+        assert_eq!(*status, 200);
+        assert_eq!(body["ARTNR"], "MeinArtikel");
+    }
+
+    // the second call is answered by the recorded resource
+    assert_eq!(upstream.handle.received_requests_for("ARTIKEL.INSERT").len(), 1);
+    assert_eq!(env.handle.unmatched_requests().len(), 0);
+    let matched = env
+        .handle
+        .received_requests_for("ARTIKEL.INSERT")
+        .into_iter()
+        .map(|request| request.matched_resource.unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(matched[0], "upstream WEBWARE");
+    assert_eq!(matched[1].starts_with("MockResource { function: ARTIKEL, method: INSERT"), true);
+    let routes = upstream
+        .handle
+        .received_requests()
+        .into_iter()
+        .map(|request| request.route)
+        .collect::<Vec<_>>();
+    assert_eq!(routes, vec![RequestRoute::Register, RequestRoute::ExecJson]);
+
+    let recorded: AppConfig = figment::Figment::from(figment::providers::Toml::file(
+        record_directory.join("mock_resources.toml"),
+    ))
+    .extract()
+    .expect("Failed to read recorded resources");
+    assert_eq!(recorded.mock_resources.len(), 1);
+    assert_eq!(recorded.mock_resources[0].function, "ARTIKEL");
+    assert_eq!(
+        recorded.mock_resources[0].data_source.read_json().unwrap(),
+        Some(serde_json::json!({ "ARTNR": "MeinArtikel" }))
+    );
+
+    std::fs::remove_dir_all(record_directory).unwrap();
+}
+
+#[tokio::test]
+async fn proxy_forwards_upstream_errors() {
+    let upstream = common::setup(false)
+        .await
+        .expect("Failed to setup upstream environment");
+
+    let config = AppConfig {
+        proxy: Some(ProxyConfig {
+            url: upstream.server.url(),
+            webservices: upstream.config.webware.webservices.clone(),
+            record_directory: None,
+        }),
+        ..Default::default()
+    };
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let responses = insert_twice(env.client, "UnbekannterArtikel").await;
+    for (status, body) in &responses {
+        assert_eq!(*status, 400);
+        assert_eq!(body["COMRESULT"]["ERRNO"], "20");
+    }
+    // nothing is recorded, so both calls are forwarded
+    assert_eq!(upstream.handle.received_requests_for("ARTIKEL.INSERT").len(), 2);
+}
+
+#[tokio::test]
+async fn proxy_without_upstream() {
+    let config = AppConfig {
+        proxy: Some(ProxyConfig {
+            url: "http://127.0.0.1:1/".to_string(),
+            webservices: Default::default(),
+            record_directory: None,
+        }),
+        ..Default::default()
+    };
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let responses = insert_twice(env.client, "MeinArtikel").await;
+    assert_eq!(responses[0].0, 502);
+    // the upstream WEBWARE didn't answer, so the requests stay unmatched
+    assert_eq!(env.handle.unmatched_requests().len(), 2);
+}
@@ -21,7 +21,7 @@ async fn register_unsuccessfully() {
         .expect("Failed to setup test environment");
 
     let client = WebwareClient::builder()
-        .webware_url(env.server.server_address().unwrap().as_str())
+        .webware_url(&env.server.url())
         .vendor_hash("a")
         .app_hash("1")
         .revision(2)
@@ -41,7 +41,7 @@ async fn register_with_invalid_revision() {
         .expect("Failed to setup test environment");
 
     let client = reqwest::Client::new();
-    let res = client.get(&format!("{}WWSVC/WWSERVICE/REGISTER/a/a/a/-1/", env.server.server_address().unwrap().as_str()))
+    let res = client.get(env.url("WWSVC/WWSERVICE/REGISTER/a/a/a/-1/"))
         .send()
         .await
         .expect("Failed to send request");
@@ -72,7 +72,7 @@ async fn deregister_unsuccessfully() {
         .expect("Failed to setup test environment");
 
     let client = WebwareClient::builder()
-        .webware_url(env.server.server_address().unwrap().as_str())
+        .webware_url(&env.server.url())
         .vendor_hash(&env.config.webware.webservices.vendor_hash)
         .app_hash(&env.config.webware.webservices.application_hash)
         .revision(env.config.webware.webservices.version)
@@ -101,7 +101,7 @@ async fn deregister_without_headers() {
         .await
         .expect("Failed to setup test environment");
     let client = reqwest::Client::new();
-    let res = client.get(&format!("{}WWSVC/WWSERVICE/DEREGISTER/{}/", env.server.server_address().unwrap().as_str(), env.config.webware.credentials.service_pass))
+    let res = client.get(format!("{}WWSVC/WWSERVICE/DEREGISTER/{}/", env.server.url(), env.config.webware.credentials.service_pass))
         .send()
         .await
         .expect("Failed to send request");
@@ -114,7 +114,7 @@ async fn deregister_with_unknown_execute_mode() {
         .await
         .expect("Failed to setup test environment");
     let client = reqwest::Client::new();
-    let res = client.get(&format!("{}WWSVC/WWSERVICE/DEREGISTER/{}/", env.server.server_address().unwrap().as_str(), env.config.webware.credentials.service_pass))
+    let res = client.get(format!("{}WWSVC/WWSERVICE/DEREGISTER/{}/", env.server.url(), env.config.webware.credentials.service_pass))
         .header("WWSVC-EXECUTE-MODE", "UNKNOWN")
         .header("WWSVC-REQID", "1")
         .header("WWSVC-TS", "Mon, 01 Jan 2000 00:00:00 GMT")
@@ -131,7 +131,7 @@ async fn deregister_with_wrong_hash() {
         .await
         .expect("Failed to setup test environment");
     let client = reqwest::Client::new();
-    let res = client.get(&format!("{}WWSVC/WWSERVICE/DEREGISTER/{}/", env.server.server_address().unwrap().as_str(), env.config.webware.credentials.service_pass))
+    let res = client.get(format!("{}WWSVC/WWSERVICE/DEREGISTER/{}/", env.server.url(), env.config.webware.credentials.service_pass))
         .header("WWSVC-EXECUTE-MODE", "SYNCHRON")
         .header("WWSVC-REQID", "1")
         .header("WWSVC-TS", "Mon, 01 Jan 2000 00:00:00 GMT")
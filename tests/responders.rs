@@ -0,0 +1,69 @@
+use std::sync::mpsc;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_mock::{ComResultBuilder, MockResourceMethod, ServiceResponse, WebserviceRequest};
+
+mod common;
+
+#[tokio::test]
+async fn responder_computes_response() {
+    let (sender, receiver) = mpsc::channel();
+    let sender = std::sync::Mutex::new(sender);
+    let config = common::default_config(false).with_responder(
+        "PREIS",
+        MockResourceMethod::Exec,
+        move |request: &WebserviceRequest| {
+            sender.lock().unwrap().send(request.clone()).unwrap();
+            match request.parameter("MENGE").and_then(|menge| menge.parse::<u32>().ok()) {
+                Some(menge) => ServiceResponse::ok(json!({ "PREIS": { "NETTO": (menge * 250).to_string() } })),
+                None => ServiceResponse::with_comresult(
+                    ComResultBuilder::new()
+                        .status(400)
+                        .code("400 Bad Request")
+                        .info("Menge fehlt")
+                        .build()
+                        .unwrap(),
+                ),
+            }
+        },
+    );
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "PREIS.EXEC", 1, &[("MENGE", "4")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["PREIS"]["NETTO"], "1000");
+
+    let (status, body) = common::exec_json(&env, "PREIS.EXEC", 1, &[]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO"], "Menge fehlt");
+
+    let received = receiver.try_iter().collect::<Vec<_>>();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].parameter("MENGE"), Some("4"));
+    assert_eq!(received[1].parameter("MENGE"), None);
+
+    let journal = env.handle.received_requests_for("PREIS.EXEC");
+    assert_eq!(
+        journal[0].matched_resource.as_deref(),
+        Some("Responder { function: PREIS, method: EXEC }")
+    );
+}
+
+#[tokio::test]
+async fn responders_sit_next_to_mock_resources() {
+    let config = common::default_config(false).with_responder(
+        "PREIS",
+        MockResourceMethod::Exec,
+        |_: &WebserviceRequest| ServiceResponse::ok(json!({ "PREIS": {} })),
+    );
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTNR"], "MeinArtikel");
+}
@@ -0,0 +1,125 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_mock::{mock, AppConfig, ApplicationConfig, FunctionSchema, MockResourceMethod};
+
+mod common;
+
+fn artikel_schema() -> FunctionSchema {
+    serde_json::from_value(json!({
+        "function": "ARTIKEL",
+        "method": "INSERT",
+        "revision": 2,
+        "required_parameters": ["ARTNR"],
+        "allowed_parameters": ["ART_51_60"],
+        "parameters": {
+            "ARTNR": { "max_length": 25 },
+            "PREIS": { "type": "Decimal" }
+        },
+        "fields": {
+            "ART_1_25": { "max_length": 25 }
+        }
+    }))
+    .expect("Failed to parse the schema")
+}
+
+fn schema_config(article_number: &str) -> AppConfig {
+    let mut config = common::default_config(false).with_mock_resource(
+        mock("ARTIKEL")
+            .method(MockResourceMethod::Insert)
+            .revision(2)
+            .any_params()
+            .respond_with_json(json!({ "ARTIKEL": { "ART_1_25": article_number } })),
+    );
+    config.schemas = vec![artikel_schema()];
+    config
+}
+
+#[tokio::test]
+async fn requests_are_validated() {
+    let env = common::setup_with_config(schema_config("A1"))
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[
+        ("ARTNR", "A1"),
+        ("PREIS", "1,50"),
+    ])
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ARTIKEL"]["ART_1_25"], "A1");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("PREIS", "1,50")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO2"], "Parameter fehlt.");
+    assert_eq!(body["COMRESULT"]["INFO3"], "ARTNR");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[
+        ("ARTNR", "A1"),
+        ("MENGE", "1"),
+    ])
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO2"], "Parameter nicht erlaubt.");
+
+    let (status, body) = common::exec_json(&env, "ARTIKEL.INSERT", 2, &[("ARTNR", &"A".repeat(26))]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO3"], "ARTNR: longer than 25 characters");
+}
+
+#[tokio::test]
+async fn drifted_data_is_rejected_at_startup() {
+    let err = common::setup_with_config(schema_config(&"A".repeat(30)))
+        .await
+        .err()
+        .expect("The server shouldn't start with data that doesn't match the schema");
+    assert_eq!(err.to_string().contains("doesn't match the schema"), true);
+}
+
+#[tokio::test]
+async fn drifted_application_data_is_rejected_at_startup() {
+    let mut config = common::default_config(false);
+    config.schemas = vec![artikel_schema()];
+    config.webware.applications = vec![ApplicationConfig {
+        mock_resources: Some(schema_config(&"A".repeat(30)).mock_resources),
+        ..Default::default()
+    }];
+    let err = common::setup_with_config(config)
+        .await
+        .err()
+        .expect("The server shouldn't start with application data that doesn't match the schema");
+    assert_eq!(err.to_string().contains("doesn't match the schema"), true);
+}
+
+#[tokio::test]
+async fn drifted_data_is_rejected_by_the_admin_api() {
+    let mut config = schema_config("A1");
+    config.admin_api = true;
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let request = env.request(reqwest::Method::POST, "__admin/mocks").json(&json!({
+        "function": "ARTIKEL",
+        "method": "INSERT",
+        "revision": 2,
+        "data_source": { "type": "String", "value": json!({ "ARTIKEL": { "ART_1_25": "A".repeat(30) } }).to_string() }
+    }));
+    let (status, body) = common::send(request).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["error"].as_str().unwrap().contains("ART_1_25"), true);
+}
+
+#[test]
+fn schemas_are_loaded_from_files() {
+    let file = std::env::temp_dir().join(format!("wwsvc-mock-schemas-{}.json", std::process::id()));
+    std::fs::write(&file, json!({ "schemas": [artikel_schema()] }).to_string()).unwrap();
+
+    let config = AppConfig {
+        schema_files: vec![file.to_string_lossy().to_string()],
+        ..Default::default()
+    };
+    let schemas = config.load_schemas().expect("Failed to load the schemas");
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(schemas.len(), 1);
+    assert_eq!(schemas[0].required_parameters, vec!["ARTNR".to_string()]);
+}
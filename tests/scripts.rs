@@ -0,0 +1,73 @@
+#![cfg(feature = "scripting")]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_mock::{FileOrString, MockResource, MockResourceMethod, ParameterMatcher};
+use wwsvc_rs::collection;
+
+mod common;
+
+fn script_resource(source: &str) -> MockResource {
+    MockResource {
+        data_source: FileOrString::Script {
+            file: None,
+            source: Some(source.to_string()),
+        },
+        function: "PREIS".to_string(),
+        method: MockResourceMethod::Exec,
+        revision: 1.into(),
+        parameters: Some(collection! {
+            "MENGE".to_string() => ParameterMatcher::regex(".*").unwrap(),
+        }),
+        ..Default::default()
+    }
+}
+
+const PRICE_SCRIPT: &str = r#"
+let menge = parse_int(request.parameters.MENGE);
+if menge == 0 {
+    comresult = #{ status: 400, info: "Menge muss größer als 0 sein" };
+    return ();
+}
+state.anfragen = (state.anfragen ?? 0) + 1;
+#{ PREIS: #{ NETTO: (menge * 250).to_string(), ANFRAGEN: state.anfragen } }
+"#;
+
+#[tokio::test]
+async fn script_computes_response() {
+    let config = common::default_config(false).with_mock_resource(script_resource(PRICE_SCRIPT));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (_, body) = common::exec_json(&env, "PREIS.EXEC", 1, &[("MENGE", "4")]).await;
+    assert_eq!(body["PREIS"], json!({ "NETTO": "1000", "ANFRAGEN": 1 }));
+    assert_eq!(body["COMRESULT"]["STATUS"], 200);
+
+    let (_, body) = common::exec_json(&env, "PREIS.EXEC", 1, &[("MENGE", "2")]).await;
+    assert_eq!(body["PREIS"], json!({ "NETTO": "500", "ANFRAGEN": 2 }));
+
+    let (status, body) = common::exec_json(&env, "PREIS.EXEC", 1, &[("MENGE", "0")]).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["COMRESULT"]["INFO"], "Menge muss größer als 0 sein");
+    assert_eq!(body.get("PREIS"), None);
+}
+
+#[tokio::test]
+async fn failing_script_returns_error() {
+    let config = common::default_config(false)
+        .with_mock_resource(script_resource(r#"throw "Preisliste fehlt";"#));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let (status, body) = common::exec_json(&env, "PREIS.EXEC", 1, &[("MENGE", "1")]).await;
+    assert_eq!(status, 500);
+    assert_eq!(body["COMRESULT"]["INFO2"], "wwsvc-mock: Script failed");
+}
+
+#[tokio::test]
+async fn invalid_script_fails_on_startup() {
+    let config = common::default_config(false).with_mock_resource(script_resource("let menge = ;"));
+    assert_eq!(wwsvc_mock::app(&config).await.is_err(), true);
+}
@@ -0,0 +1,95 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{ApplicationConfig, FileOrString, MockResource, MockResourceMethod, ResponseSequence, SequenceMode};
+use wwsvc_rs::{collection, futures::FutureExt, Method, WebwareClient};
+
+mod common;
+
+fn job_resource(mode: SequenceMode) -> MockResource {
+    job_resource_with_status(mode, "DONE")
+}
+
+fn job_resource_with_status(mode: SequenceMode, status: &str) -> MockResource {
+    MockResource {
+        data_source: FileOrString::Empty,
+        function: "JOB".to_string(),
+        method: MockResourceMethod::Exec,
+        revision: 1.into(),
+        parameters: None,
+        sequence: Some(ResponseSequence {
+            responses: vec![
+                FileOrString::String { value: r#"{"STATUS": "RUNNING"}"#.to_string() },
+                FileOrString::String { value: r#"{"STATUS": "RUNNING"}"#.to_string() },
+                FileOrString::String { value: format!(r#"{{"STATUS": "{}"}}"#, status) },
+            ],
+            mode,
+        }),
+        ..Default::default()
+    }
+}
+
+async fn poll(client: WebwareClient, times: usize) -> Vec<String> {
+    client
+        .with_registered(|client| {
+            async move {
+                let mut statuses = vec![];
+                for _ in 0..times {
+                    let body = client
+                        .request_as_response(Method::PUT, "JOB.EXEC", 1, collection! {}, None)
+                        .await?
+                        .json::<serde_json::Value>()
+                        .await?;
+                    statuses.push(body["STATUS"].as_str().unwrap_or_default().to_string());
+                }
+                Ok::<_, anyhow::Error>(statuses)
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request")
+}
+
+#[tokio::test]
+async fn sequence_repeats_last_response() {
+    let config = common::default_config(false).with_mock_resource(job_resource(SequenceMode::RepeatLast));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    assert_eq!(poll(env.client, 4).await, vec!["RUNNING", "RUNNING", "DONE", "DONE"]);
+}
+
+#[tokio::test]
+async fn sequence_cycles() {
+    let config = common::default_config(false).with_mock_resource(job_resource(SequenceMode::Cycle));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    assert_eq!(poll(env.client, 4).await, vec!["RUNNING", "RUNNING", "DONE", "RUNNING"]);
+}
+
+#[tokio::test]
+async fn equal_resources_have_separate_sequences() {
+    let mut config = common::default_config(false).with_mock_resource(job_resource(SequenceMode::RepeatLast));
+    config.webware.applications = vec![ApplicationConfig {
+        mock_resources: Some(vec![job_resource_with_status(SequenceMode::RepeatLast, "FAILED")]),
+        ..Default::default()
+    }];
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let application = &env.config.webware.applications[0];
+    let application_client = WebwareClient::builder()
+        .webware_url(&env.server.url())
+        .vendor_hash(&application.webservices.vendor_hash)
+        .app_hash(&application.webservices.application_hash)
+        .revision(application.webservices.version)
+        .secret(&application.webservices.application_secret)
+        .allow_insecure(true)
+        .build();
+
+    assert_eq!(poll(application_client, 3).await, vec!["RUNNING", "RUNNING", "FAILED"]);
+    assert_eq!(poll(env.client, 3).await, vec!["RUNNING", "RUNNING", "DONE"]);
+}
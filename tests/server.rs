@@ -0,0 +1,56 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::MockServer;
+
+mod common;
+
+#[tokio::test]
+async fn mock_server_answers_requests() {
+    let config = common::default_config(false);
+    let server = MockServer::start(config.clone())
+        .await
+        .expect("Failed to start mock server");
+
+    let response = reqwest::Client::new()
+        .put(format!("{}WWSVC/EXECJSON", server.url()))
+        .json(&common::exec_json_body(&config, "GET_RELATION.EXEC", 1, &[("NR", "65"), ("P1", "Hallo")]))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response body");
+    assert_eq!(body["GET_RESULT"], "Hallo");
+
+    assert_eq!(server.handle().received_requests_for("GET_RELATION.EXEC").len(), 1);
+
+    let url = server.url();
+    server.shutdown().await.expect("Failed to shut down mock server");
+    assert_eq!(reqwest::get(url).await.is_err(), true);
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn mock_server_client() {
+    use wwsvc_rs::{collection, futures::FutureExt, Method};
+
+    let server = MockServer::start(common::default_config(false))
+        .await
+        .expect("Failed to start mock server");
+
+    let response = server
+        .client()
+        .with_registered(|client| {
+            async move {
+                client
+                    .request_as_response(Method::PUT, "GET_RELATION.EXEC", 1, collection! {
+                        "NR" => "65",
+                        "P1" => "Hallo",
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+}
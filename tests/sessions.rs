@@ -0,0 +1,186 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_mock::{AppConfig, FaultConfig, FaultKind};
+
+mod common;
+
+async fn setup_sessions(max_service_passes: Option<usize>) -> common::TestEnvironment {
+    setup_sessions_with_authentication(max_service_passes, true).await
+}
+
+async fn setup_sessions_with_authentication(
+    max_service_passes: Option<usize>,
+    authenticate_requests: bool,
+) -> common::TestEnvironment {
+    let mut config = common::default_config(false);
+    config.webware.authenticate_requests = authenticate_requests;
+    config.webware.sessions.enabled = true;
+    config.webware.sessions.max_service_passes = max_service_passes;
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+async fn register(env: &common::TestEnvironment) -> reqwest::Response {
+    let webservices = &env.config.webware.webservices;
+    reqwest::Client::new()
+        .get(format!(
+            "{}WWSVC/WWSERVICE/REGISTER/{}/{}/{}/{}/",
+            env.server.url(),
+            webservices.vendor_hash,
+            webservices.application_hash,
+            webservices.application_secret,
+            webservices.version
+        ))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+async fn register_pass(env: &common::TestEnvironment) -> (String, String) {
+    let body: serde_json::Value = register(env)
+        .await
+        .json()
+        .await
+        .expect("Failed to parse response body");
+    (
+        body["SERVICEPASS"]["PASSID"].as_str().unwrap().to_string(),
+        body["SERVICEPASS"]["APPID"].as_str().unwrap().to_string(),
+    )
+}
+
+fn signed(request: reqwest::RequestBuilder, application_id: &str) -> reqwest::RequestBuilder {
+    let ts = "Mon, 01 Jan 2000 00:00:00 GMT";
+    request
+        .header("WWSVC-EXECUTE-MODE", "SYNCHRON")
+        .header("WWSVC-REQID", "1")
+        .header("WWSVC-TS", ts)
+        .header(
+            "WWSVC-HASH",
+            format!("{:x}", md5::compute(format!("{}{}", application_id, ts))),
+        )
+}
+
+async fn exec(env: &common::TestEnvironment, service_pass: &str, application_id: &str) -> u16 {
+    let mut body = common::exec_json_body(&env.config, "ARTIKEL.INSERT", 2, &[("ARTNR", "MeinArtikel")]);
+    body["WWSVC_PASSINFO"]["SERVICEPASS"] = json!(service_pass);
+    let request = reqwest::Client::new().put(env.url("WWSVC/EXECJSON")).json(&body);
+    signed(request, application_id)
+        .send()
+        .await
+        .expect("Failed to send request")
+        .status()
+        .as_u16()
+}
+
+async fn deregister(env: &common::TestEnvironment, service_pass: &str, application_id: &str) -> u16 {
+    let request = reqwest::Client::new().get(format!(
+        "{}WWSVC/WWSERVICE/DEREGISTER/{}/",
+        env.server.url(),
+        service_pass
+    ));
+    signed(request, application_id)
+        .send()
+        .await
+        .expect("Failed to send request")
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn register_issues_fresh_passes() {
+    let env = setup_sessions(None).await;
+
+    let (first_pass, first_id) = register_pass(&env).await;
+    let (second_pass, second_id) = register_pass(&env).await;
+    assert_ne!(first_pass, second_pass);
+    assert_ne!(first_id, second_id);
+    assert_ne!(first_pass, env.config.webware.credentials.service_pass);
+
+    assert_eq!(exec(&env, &first_pass, &first_id).await, 200);
+    assert_eq!(exec(&env, &second_pass, &second_id).await, 200);
+}
+
+#[tokio::test]
+async fn deregistered_pass_is_rejected() {
+    let env = setup_sessions(None).await;
+
+    let (service_pass, application_id) = register_pass(&env).await;
+    assert_eq!(deregister(&env, &service_pass, &application_id).await, 200);
+    assert_eq!(exec(&env, &service_pass, &application_id).await, 404);
+    assert_eq!(deregister(&env, &service_pass, &application_id).await, 404);
+}
+
+#[tokio::test]
+async fn never_issued_pass_is_rejected() {
+    let env = setup_sessions(None).await;
+    let credentials = &env.config.webware.credentials;
+
+    assert_eq!(exec(&env, &credentials.service_pass, &credentials.application_id).await, 404);
+    assert_eq!(deregister(&env, &credentials.service_pass, &credentials.application_id).await, 404);
+}
+
+#[tokio::test]
+async fn unknown_pass_is_rejected_without_authentication() {
+    let env = setup_sessions_with_authentication(None, false).await;
+    let credentials = &env.config.webware.credentials;
+
+    assert_eq!(exec(&env, &credentials.service_pass, &credentials.application_id).await, 404);
+
+    let (service_pass, application_id) = register_pass(&env).await;
+    assert_eq!(exec(&env, &service_pass, "unsigned").await, 200);
+    assert_eq!(deregister(&env, &service_pass, &application_id).await, 200);
+    assert_eq!(exec(&env, &service_pass, &application_id).await, 404);
+}
+
+#[tokio::test]
+async fn register_respects_limit() {
+    let env = setup_sessions(Some(1)).await;
+
+    let (service_pass, application_id) = register_pass(&env).await;
+    assert_eq!(register(&env).await.status().as_u16(), 406);
+    assert_eq!(deregister(&env, &service_pass, &application_id).await, 200);
+    assert_eq!(register(&env).await.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn faulty_register_releases_the_pass() {
+    let mut config = common::default_config(false);
+    config.webware.sessions.enabled = true;
+    config.webware.sessions.max_service_passes = Some(1);
+    config.faults = Some(FaultConfig {
+        probability: 0.5,
+        kinds: vec![FaultKind::ServiceUnavailable],
+    });
+    // with this seed, the first three calls fail
+    config.seed = Some(2);
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let mut statuses = vec![];
+    for _ in 0..4 {
+        statuses.push(register(&env).await.status().as_u16());
+    }
+    assert_eq!(statuses, vec![503, 503, 503, 200]);
+}
+
+#[tokio::test]
+async fn client_works_with_sessions() {
+    let env = setup_sessions(None).await;
+
+    let client = env
+        .client
+        .register()
+        .await
+        .expect("Failed to register the client");
+    client
+        .deregister()
+        .await
+        .expect("Failed to deregister the client");
+}
+
+#[test]
+fn sessions_are_disabled_by_default() {
+    assert_eq!(AppConfig::default().webware.sessions.enabled, false);
+}
@@ -0,0 +1,172 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wwsvc_mock::{FileOrString, MockResource, MockResourceMethod, StatefulFunction};
+use wwsvc_rs::{collection, futures::FutureExt, Method};
+
+mod common;
+
+async fn setup_stateful() -> common::TestEnvironment {
+    let config = common::default_config(false).with_stateful_function(StatefulFunction {
+        function: "KUNDE".to_string(),
+        key_field: "KUN_1_10".to_string(),
+        key_parameter: Some("KUNDENNR".to_string()),
+        list_name: Some("KUNDENLISTE".to_string()),
+        item_name: None,
+        seed: FileOrString::String {
+            value: json!({
+                "KUNDENLISTE": {
+                    "ANZAHL": "1",
+                    "KUNDE": [{ "KUN_1_10": "10000", "SNR": "1" }]
+                }
+            })
+            .to_string(),
+        },
+    });
+    common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment")
+}
+
+#[tokio::test]
+async fn insert_then_get() {
+    let env = setup_stateful().await;
+
+    let (insert, get) = env
+        .client
+        .with_registered(|client| {
+            async {
+                let insert = client
+                    .request_as_response(Method::PUT, "KUNDE.INSERT", 1, collection! {
+                        "KUNDENNR" => "10001",
+                        "KUN_11_40" => "Muster GmbH",
+                    }, None)
+                    .await?
+                    .json::<serde_json::Value>()
+                    .await?;
+                let get = client
+                    .request_as_response(Method::PUT, "KUNDE.GET", 1, collection! {}, None)
+                    .await?
+                    .json::<serde_json::Value>()
+                    .await?;
+                Ok::<_, anyhow::Error>((insert, get))
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+
+    assert_eq!(insert["KUNDE"], json!({ "KUN_1_10": "10001", "KUN_11_40": "Muster GmbH", "SNR": "2" }));
+    assert_eq!(get["COMRESULT"]["STATUS"], 200);
+    assert_eq!(get["KUNDENLISTE"]["ANZAHL"], "2");
+    assert_eq!(get["KUNDENLISTE"]["KUNDE"][1]["KUN_11_40"], "Muster GmbH");
+}
+
+#[tokio::test]
+async fn put_then_delete() {
+    let env = setup_stateful().await;
+
+    let (put, delete, get) = env
+        .client
+        .with_registered(|client| {
+            async {
+                let put = client
+                    .request_as_response(Method::PUT, "KUNDE.PUT", 1, collection! {
+                        "KUNDENNR" => "10000",
+                        "KUN_11_40" => "Neuer Name",
+                    }, None)
+                    .await?
+                    .json::<serde_json::Value>()
+                    .await?;
+                let delete = client
+                    .request_as_response(Method::PUT, "KUNDE.DELETE", 1, collection! {
+                        "KUNDENNR" => "10000",
+                    }, None)
+                    .await?
+                    .json::<serde_json::Value>()
+                    .await?;
+                let get = client
+                    .request_as_response(Method::PUT, "KUNDE.GET", 1, collection! {}, None)
+                    .await?
+                    .json::<serde_json::Value>()
+                    .await?;
+                Ok::<_, anyhow::Error>((put, delete, get))
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+
+    assert_eq!(put["KUNDE"]["KUN_11_40"], "Neuer Name");
+    assert_eq!(delete["COMRESULT"]["STATUS"], 200);
+    assert_eq!(get["KUNDENLISTE"], json!({ "ANZAHL": "0", "KUNDE": [] }));
+}
+
+#[tokio::test]
+async fn insert_existing_record() {
+    let env = setup_stateful().await;
+
+    let response = env
+        .client
+        .with_registered(|client| {
+            async {
+                client
+                    .request_as_response(Method::PUT, "KUNDE.INSERT", 1, collection! {
+                        "KUNDENNR" => "10000",
+                    }, None)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+
+    assert_eq!(response.status().as_u16(), 400);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response body");
+    assert_eq!(body["COMRESULT"]["INFO2"], "Datensatz existiert bereits.");
+    assert_eq!(body["COMRESULT"]["ERRNO"], "1");
+}
+
+#[tokio::test]
+async fn mock_resource_for_another_revision() {
+    let config = common::default_config(false)
+        .with_stateful_function(StatefulFunction {
+            function: "KUNDE".to_string(),
+            key_field: "KUN_1_10".to_string(),
+            key_parameter: None,
+            list_name: None,
+            item_name: None,
+            seed: FileOrString::String { value: json!([{ "KUN_1_10": "10000" }]).to_string() },
+        })
+        .with_mock_resource(MockResource {
+            function: "KUNDE".to_string(),
+            method: MockResourceMethod::Get,
+            revision: 2.into(),
+            ..Default::default()
+        });
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let body = env
+        .client
+        .with_registered(|client| {
+            async {
+                client
+                    .request_as_response(Method::PUT, "KUNDE.GET", 1, collection! {}, None)
+                    .await?
+                    .json::<serde_json::Value>()
+                    .await
+                    .map_err(anyhow::Error::from)
+            }
+            .boxed()
+        })
+        .await
+        .expect("Failed to register the client")
+        .expect("Failed to send request");
+
+    assert_eq!(body["COMRESULT"]["STATUS"], 200);
+    assert_eq!(body["KUNDELISTE"]["KUNDE"][0]["KUN_1_10"], "10000");
+}
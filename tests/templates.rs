@@ -0,0 +1,65 @@
+use pretty_assertions::assert_eq;
+use wwsvc_mock::{FileOrString, MockResource, MockResourceMethod, ParameterMatcher};
+use wwsvc_rs::collection;
+
+mod common;
+
+fn echo_resource(template: bool) -> MockResource {
+    MockResource {
+        data_source: FileOrString::String {
+            value: r#"{"ARTIKEL": {"ARTNR": "{{param.ARTNR}}", "SNR": "{{call}}", "PASS": "{{service_pass}}"}}"#.to_string(),
+        },
+        function: "ARTIKEL".to_string(),
+        method: MockResourceMethod::Insert,
+        revision: 1.into(),
+        template,
+        parameters: Some(collection! {
+            "ARTNR".to_string() => ParameterMatcher::regex(".*").unwrap(),
+        }),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn template_echoes_parameters() {
+    let config = common::default_config(false).with_mock_resource(echo_resource(true));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 1, &[("ARTNR", "Artikel \"A\"")]).await.1;
+    assert_eq!(body["ARTIKEL"]["ARTNR"], "Artikel \"A\"");
+    assert_eq!(body["ARTIKEL"]["SNR"], "1");
+    assert_eq!(body["ARTIKEL"]["PASS"], env.config.webware.credentials.service_pass.as_str());
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 1, &[("ARTNR", "B")]).await.1;
+    assert_eq!(body["ARTIKEL"]["ARTNR"], "B");
+    assert_eq!(body["ARTIKEL"]["SNR"], "2");
+}
+
+#[tokio::test]
+async fn templates_are_opt_in() {
+    let config = common::default_config(false).with_mock_resource(echo_resource(false));
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 1, &[("ARTNR", "B")]).await.1;
+    assert_eq!(body["ARTIKEL"]["ARTNR"], "{{param.ARTNR}}");
+}
+
+#[tokio::test]
+async fn invalid_template_fails() {
+    let mut resource = echo_resource(true);
+    resource.data_source = FileOrString::String {
+        value: r#"{"ARTIKEL": {"ARTNR": {{param.ARTNR}}}}"#.to_string(),
+    };
+    let config = common::default_config(false).with_mock_resource(resource);
+    let env = common::setup_with_config(config)
+        .await
+        .expect("Failed to setup test environment");
+
+    let body = common::exec_json(&env, "ARTIKEL.INSERT", 1, &[("ARTNR", "B")]).await.1;
+    assert_eq!(body["COMRESULT"]["STATUS"], 500);
+    assert_eq!(body["COMRESULT"]["INFO2"], "wwsvc-mock: Template failed");
+}